@@ -0,0 +1,24 @@
+use std::env;
+use std::process::Command;
+
+fn main() {
+    println!("cargo::rustc-check-cfg=cfg(kanau_nightly)");
+    println!("cargo::rerun-if-env-changed=RUSTC");
+
+    // the `try_trait` feature needs unstable library features, so it's only enabled on nightly
+    let rustc = env::var_os("RUSTC").unwrap_or_else(|| "rustc".into());
+    let nightly = Command::new(rustc)
+        .arg("--version")
+        .output()
+        .ok()
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .is_some_and(|version| version.contains("-nightly") || version.contains("-dev"));
+    if nightly {
+        println!("cargo::rustc-cfg=kanau_nightly");
+    } else if env::var_os("CARGO_FEATURE_TRY_TRAIT").is_some() {
+        println!(
+            "cargo::warning=the `try_trait` feature of kanau requires a nightly compiler, \
+             `?` on EarlyReturn is disabled"
+        );
+    }
+}
@@ -1,4 +1,5 @@
 mod message;
+mod processor;
 
 #[proc_macro_derive(BincodeMessageDe)]
 pub fn derive_bincode_byte_des(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
@@ -39,3 +40,11 @@ pub fn derive_prost_byte_des(input: proc_macro::TokenStream) -> proc_macro::Toke
 pub fn derive_prost_byte_ser(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     message::prost::derive_proto_ser(input)
 }
+
+#[proc_macro_attribute]
+pub fn processor(
+    attr: proc_macro::TokenStream,
+    item: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    processor::processor(attr, item)
+}
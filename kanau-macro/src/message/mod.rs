@@ -1,4 +1,4 @@
 pub(crate) mod bincode;
-pub(crate) mod serde_json;
+pub(crate) mod prost;
 pub(crate) mod rkyv;
-pub(crate) mod prost;
\ No newline at end of file
+pub(crate) mod serde_json;
@@ -0,0 +1,277 @@
+use convert_case::{Case, Casing};
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{
+    Attribute, FnArg, ImplItem, Item, ItemFn, ItemImpl, Meta, Pat, PatType, ReturnType, Signature,
+    Type, parse_macro_input,
+};
+
+enum ProcessorKind {
+    // `&self`
+    Ref,
+    // `self: Arc<Self>`
+    Final,
+}
+
+pub fn processor(attr: TokenStream, item: TokenStream) -> TokenStream {
+    if !attr.is_empty() {
+        return syn::Error::new(Span::call_site(), "#[processor] takes no arguments")
+            .to_compile_error()
+            .into();
+    }
+    let item = parse_macro_input!(item as Item);
+    let result = match item {
+        Item::Impl(item_impl) => expand_impl(item_impl),
+        Item::Fn(item_fn) => expand_fn(item_fn),
+        other => Err(syn::Error::new_spanned(
+            other,
+            "#[processor] can only be applied to an impl block or an async fn",
+        )),
+    };
+    result.unwrap_or_else(|e| e.to_compile_error()).into()
+}
+
+fn output_type(sig: &Signature) -> TokenStream2 {
+    match &sig.output {
+        ReturnType::Default => quote! { () },
+        ReturnType::Type(_, ty) => quote! { #ty },
+    }
+}
+
+fn check_signature(sig: &Signature) -> syn::Result<()> {
+    if sig.asyncness.is_none() {
+        return Err(syn::Error::new_spanned(
+            sig.fn_token,
+            "#[processor] functions must be async",
+        ));
+    }
+    if !sig.generics.params.is_empty() || sig.generics.where_clause.is_some() {
+        return Err(syn::Error::new_spanned(
+            &sig.generics,
+            "#[processor] functions can't have their own generic parameters",
+        ));
+    }
+    Ok(())
+}
+
+fn typed_arg(arg: &FnArg) -> syn::Result<&PatType> {
+    match arg {
+        FnArg::Typed(pat_type) => Ok(pat_type),
+        FnArg::Receiver(receiver) => Err(syn::Error::new_spanned(receiver, "unexpected receiver")),
+    }
+}
+
+fn receiver_kind(arg: Option<&FnArg>, sig: &Signature) -> syn::Result<ProcessorKind> {
+    let Some(FnArg::Receiver(receiver)) = arg else {
+        return Err(syn::Error::new_spanned(
+            sig,
+            "#[processor] methods must take `&self` or `self: Arc<Self>`",
+        ));
+    };
+    if receiver.colon_token.is_none() {
+        if receiver.reference.is_some() && receiver.mutability.is_none() {
+            return Ok(ProcessorKind::Ref);
+        }
+    } else if let Type::Path(path) = receiver.ty.as_ref()
+        && path.path.segments.last().is_some_and(|s| s.ident == "Arc")
+    {
+        return Ok(ProcessorKind::Final);
+    }
+    Err(syn::Error::new_spanned(
+        receiver,
+        "#[processor] methods must take `&self` or `self: Arc<Self>`",
+    ))
+}
+
+// `#[processor]` or `#[kanau::processor]` on a method of the impl block
+fn is_marker(attr: &Attribute) -> bool {
+    matches!(attr.meta, Meta::Path(_))
+        && attr
+            .path()
+            .segments
+            .last()
+            .is_some_and(|s| s.ident == "processor")
+}
+
+fn expand_impl(mut item_impl: ItemImpl) -> syn::Result<TokenStream2> {
+    if let Some((_, path, _)) = &item_impl.trait_ {
+        return Err(syn::Error::new_spanned(
+            path,
+            "#[processor] must be applied to an inherent impl block",
+        ));
+    }
+
+    // only the marked methods are processors, the markers are removed
+    let mut methods = Vec::new();
+    for item in &mut item_impl.items {
+        let ImplItem::Fn(method) = item else {
+            continue;
+        };
+        let attrs = method.attrs.len();
+        method.attrs.retain(|attr| !is_marker(attr));
+        if method.attrs.len() != attrs {
+            methods.push(method.sig.clone());
+        }
+    }
+    if methods.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &item_impl.self_ty,
+            "mark the methods implementing a processor with #[processor]",
+        ));
+    }
+
+    let self_ty = &item_impl.self_ty;
+    let (impl_generics, _, where_clause) = item_impl.generics.split_for_impl();
+
+    let mut impls = Vec::new();
+    let mut seen = Vec::new();
+    for sig in &methods {
+        check_signature(sig)?;
+        let kind = receiver_kind(sig.inputs.first(), sig)?;
+        let args: Vec<_> = sig.inputs.iter().skip(1).collect();
+        let input = match (args.as_slice(), &kind) {
+            ([input], _) => typed_arg(input)?,
+            ([_, _], ProcessorKind::Ref) => {
+                return Err(syn::Error::new_spanned(
+                    &sig.inputs,
+                    "RefProcessor can't be implemented with an async fn, because its future can't \
+                     borrow both `self` and `deps`; implement RefProcessor manually",
+                ));
+            }
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &sig.inputs,
+                    "#[processor] methods must take exactly one input besides the receiver",
+                ));
+            }
+        };
+        let input_ty = &input.ty;
+        let output_ty = output_type(sig);
+        let method_name = &sig.ident;
+        // the same input and output can only be implemented once per flavor
+        let key = (
+            matches!(kind, ProcessorKind::Final),
+            quote! { #input_ty }.to_string(),
+            output_ty.to_string(),
+        );
+        if seen.contains(&key) {
+            return Err(syn::Error::new_spanned(
+                sig,
+                "another #[processor] method of this impl block has the same input and output",
+            ));
+        }
+        seen.push(key);
+        // point the `Send` errors at the user's method
+        let span = sig.span();
+        impls.push(match kind {
+            ProcessorKind::Ref => quote_spanned! {span=>
+                impl #impl_generics kanau::processor::Processor<#input_ty, #output_ty> for #self_ty
+                #where_clause
+                {
+                    fn process(
+                        &self,
+                        input: #input_ty,
+                    ) -> impl ::core::future::Future<Output = #output_ty> + ::core::marker::Send {
+                        <#self_ty>::#method_name(self, input)
+                    }
+                }
+            },
+            ProcessorKind::Final => quote_spanned! {span=>
+                impl #impl_generics kanau::processor::FinalProcessor<#input_ty, #output_ty>
+                    for #self_ty
+                #where_clause
+                {
+                    fn process(
+                        state: ::std::sync::Arc<Self>,
+                        input: #input_ty,
+                    ) -> impl ::core::future::Future<Output = #output_ty> + ::core::marker::Send {
+                        <#self_ty>::#method_name(state, input)
+                    }
+                }
+            },
+        });
+    }
+
+    Ok(quote! {
+        #item_impl
+        #(#impls)*
+    })
+}
+
+fn expand_fn(item_fn: ItemFn) -> syn::Result<TokenStream2> {
+    let ItemFn {
+        attrs, vis, sig, ..
+    } = &item_fn;
+    check_signature(sig)?;
+    let args: Vec<_> = sig.inputs.iter().collect();
+    let Some((input, deps)) = args.split_last() else {
+        return Err(syn::Error::new_spanned(
+            sig,
+            "#[processor] functions must take an input as the last parameter",
+        ));
+    };
+    let input_ty = &typed_arg(input)?.ty;
+    let output_ty = output_type(sig);
+
+    let mut field_names = Vec::new();
+    let mut field_types = Vec::new();
+    for dep in deps {
+        let dep = typed_arg(dep)?;
+        let Pat::Ident(pat_ident) = dep.pat.as_ref() else {
+            return Err(syn::Error::new_spanned(
+                &dep.pat,
+                "dependencies of a #[processor] function must be named with an identifier",
+            ));
+        };
+        let Type::Reference(reference) = dep.ty.as_ref() else {
+            return Err(syn::Error::new_spanned(
+                &dep.ty,
+                "dependencies of a #[processor] function must be taken by shared reference",
+            ));
+        };
+        if reference.mutability.is_some() {
+            return Err(syn::Error::new_spanned(
+                &dep.ty,
+                "dependencies of a #[processor] function must be taken by shared reference",
+            ));
+        }
+        field_names.push(&pat_ident.ident);
+        field_types.push(&reference.elem);
+    }
+
+    let fn_name = &sig.ident;
+    let struct_name = format_ident!(
+        "{}",
+        fn_name.to_string().to_case(Case::Pascal),
+        span = fn_name.span()
+    );
+    let span = sig.span();
+    let processor_impl = quote_spanned! {span=>
+        impl kanau::processor::Processor<#input_ty, #output_ty> for #struct_name {
+            fn process(
+                &self,
+                input: #input_ty,
+            ) -> impl ::core::future::Future<Output = #output_ty> + ::core::marker::Send {
+                #fn_name(#(&self.#field_names,)* input)
+            }
+        }
+    };
+
+    // other attributes, like `#[allow]` or `#[tracing::instrument]`, stay on the function
+    let docs = attrs.iter().filter(|attr| attr.path().is_ident("doc"));
+    Ok(quote! {
+        #(#docs)*
+        #vis struct #struct_name {
+            #(
+                #[allow(missing_docs)]
+                #vis #field_names: #field_types,
+            )*
+        }
+
+        #processor_impl
+
+        #item_fn
+    })
+}
@@ -0,0 +1,95 @@
+use crate::flow::EarlyReturn;
+use crate::processor::Processor;
+use axum::extract::{FromRequest, Request};
+use axum::handler::Handler;
+use axum::response::{IntoResponse, Response};
+use std::fmt::Debug;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::sync::Arc;
+
+/// ## ProcessorHandler
+///
+/// A [Processor] used as an axum route handler.
+///
+/// The input of the processor is extracted from the request, so it can be any axum extractor,
+/// like `Json<T>`, `Path<T>`, `Query<T>`, or a tuple of them. If the extraction fails,
+/// the rejection is returned as the response. The output is converted with [IntoResponse].
+///
+/// Use [ProcessorExt::map_input](crate::processor::ProcessorExt::map_input) to unwrap the
+/// extractor if the processor takes the plain value:
+///
+/// ```ignore
+/// let handler = ProcessorHandler::new(create_user.map_input(|Json(body)| body));
+/// let app = Router::new().route("/users", post(handler));
+/// ```
+pub struct ProcessorHandler<X, O, P: Processor<X, O>> {
+    processor: Arc<P>,
+    // `fn(X) -> O` keeps the handler `Sync` when the input or output isn't, like `Response`
+    _phantom: PhantomData<fn(X) -> O>,
+}
+
+impl<X, O, P: Processor<X, O>> ProcessorHandler<X, O, P> {
+    /// Create a new handler from a processor.
+    pub fn new(processor: P) -> Self {
+        Self::from_arc(Arc::new(processor))
+    }
+
+    /// Create a new handler from a shared processor.
+    pub fn from_arc(processor: Arc<P>) -> Self {
+        Self {
+            processor,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<X, O, P: Processor<X, O>> Clone for ProcessorHandler<X, O, P> {
+    fn clone(&self) -> Self {
+        Self {
+            processor: self.processor.clone(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<X, O, P: Processor<X, O> + Debug> Debug for ProcessorHandler<X, O, P> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProcessorHandler")
+            .field("processor", &self.processor)
+            .finish()
+    }
+}
+
+impl<M, X, O, P, S> Handler<(M, X), S> for ProcessorHandler<X, O, P>
+where
+    M: 'static,
+    X: FromRequest<S, M> + Send + 'static,
+    O: IntoResponse + Send + 'static,
+    P: Processor<X, O> + Send + Sync + 'static,
+    S: Send + Sync + 'static,
+{
+    type Future = Pin<Box<dyn Future<Output = Response> + Send>>;
+
+    fn call(self, req: Request, state: S) -> Self::Future {
+        Box::pin(async move {
+            let input = match X::from_request(req, &state).await {
+                Ok(input) => input,
+                Err(rejection) => return rejection.into_response(),
+            };
+            self.processor.process(input).await.into_response()
+        })
+    }
+}
+
+/// Both the returned value and the expression value are converted into a response,
+/// so a processor returning [EarlyReturn] can respond early with an error page,
+/// a redirection, etc.
+impl<R: IntoResponse, E: IntoResponse> IntoResponse for EarlyReturn<R, E> {
+    fn into_response(self) -> Response {
+        match self {
+            EarlyReturn::Expr(e) => e.into_response(),
+            EarlyReturn::Return(r) => r.into_response(),
+        }
+    }
+}
@@ -1,10 +1,42 @@
 use crate::processor::Processor;
+#[cfg(all(feature = "try_trait", kanau_nightly))]
+use std::convert::Infallible;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+use std::ops::ControlFlow;
+
+mod validation;
+pub use validation::Validation;
 
 /// ## EarlyReturn
 ///
 /// an enum that shows a value returned from a function should be returned early or not.
 ///
-/// Usually used with [early_return!] or [monad_early_return!] macro.
+/// Usually used with [early_return!](crate::early_return!) or
+/// [monad_early_return!](crate::monad_early_return!) macro.
+///
+/// With the `try_trait` feature on a nightly compiler, the `?` operator works on [EarlyReturn] in
+/// a function returning [EarlyReturn]. On stable Rust, the feature only emits a build warning,
+/// convert it into a [ControlFlow] instead, which supports `?` in a function returning
+/// [ControlFlow]:
+///
+/// ```
+/// use kanau::flow::EarlyReturn;
+/// use std::ops::ControlFlow;
+///
+/// fn half(x: u32) -> EarlyReturn<&'static str, u32> {
+///     if x.is_multiple_of(2) { EarlyReturn::Expr(x / 2) } else { EarlyReturn::Return("odd") }
+/// }
+///
+/// fn quarter(x: u32) -> ControlFlow<&'static str, u32> {
+///     let x = half(x).into_control_flow()?;
+///     half(x).into_control_flow()
+/// }
+///
+/// assert_eq!(EarlyReturn::from(quarter(8)), EarlyReturn::Expr(2));
+/// assert_eq!(EarlyReturn::from(quarter(6)), EarlyReturn::Return("odd"));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum EarlyReturn<Return, Expr = ()> {
     /// Treat the value as an expression.
     Expr(Expr),
@@ -90,7 +122,41 @@ impl<R, E> EarlyReturn<R, E> {
             EarlyReturn::Return(r) => EarlyReturn::Return(r),
         }
     }
-    
+
+    /// Convert into a [ControlFlow], [EarlyReturn::Return] being [ControlFlow::Break].
+    pub fn into_control_flow(self) -> ControlFlow<R, E> {
+        self.into()
+    }
+
+    /// Convert into a [Result], the expression value being [Ok] and the return value [Err].
+    pub fn into_result(self) -> Result<E, R> {
+        match self {
+            EarlyReturn::Expr(e) => Ok(e),
+            EarlyReturn::Return(r) => Err(r),
+        }
+    }
+
+    /// Create an early return from an option. [None] returns early with `none_value`.
+    pub fn from_option(option: Option<E>, none_value: R) -> Self {
+        match option {
+            Some(e) => EarlyReturn::Expr(e),
+            None => EarlyReturn::Return(none_value),
+        }
+    }
+
+    /// Fold an iterator with a function that may return early, like [Iterator::try_fold].
+    ///
+    /// The first [EarlyReturn::Return] stops the iteration and is returned.
+    pub fn try_fold<Iter: IntoIterator, F: FnMut(E, Iter::Item) -> EarlyReturn<R, E>>(
+        iter: Iter,
+        init: E,
+        mut f: F,
+    ) -> Self {
+        iter.into_iter()
+            .try_fold(init, |acc, item| f(acc, item).into_control_flow())
+            .into()
+    }
+
     /// Create an early return from a result. The error is treated as the return value.
     pub fn from_result<Ok>(res: Result<E, R>) -> EarlyReturn<Result<Ok, R>, E> {
         match res {
@@ -100,6 +166,76 @@ impl<R, E> EarlyReturn<R, E> {
     }
 }
 
+impl<R, E> From<EarlyReturn<R, E>> for ControlFlow<R, E> {
+    fn from(value: EarlyReturn<R, E>) -> Self {
+        match value {
+            EarlyReturn::Expr(e) => ControlFlow::Continue(e),
+            EarlyReturn::Return(r) => ControlFlow::Break(r),
+        }
+    }
+}
+
+impl<R, E> From<ControlFlow<R, E>> for EarlyReturn<R, E> {
+    fn from(value: ControlFlow<R, E>) -> Self {
+        match value {
+            ControlFlow::Continue(e) => EarlyReturn::Expr(e),
+            ControlFlow::Break(r) => EarlyReturn::Return(r),
+        }
+    }
+}
+
+/// Collect the expression values, or return early with the first return value.
+impl<R, E, V: FromIterator<E>> FromIterator<EarlyReturn<R, E>> for EarlyReturn<R, V> {
+    fn from_iter<Iter: IntoIterator<Item = EarlyReturn<R, E>>>(iter: Iter) -> Self {
+        let mut ret = None;
+        let values = iter
+            .into_iter()
+            .map_while(|item| match item {
+                EarlyReturn::Expr(e) => Some(e),
+                EarlyReturn::Return(r) => {
+                    ret = Some(r);
+                    None
+                }
+            })
+            .collect();
+        match ret {
+            Some(r) => EarlyReturn::Return(r),
+            None => EarlyReturn::Expr(values),
+        }
+    }
+}
+
+impl<R, E> EarlyReturn<R, Option<E>> {
+    /// Return early with `none_value` if the expression value is [None].
+    pub fn ok_or_return(self, none_value: R) -> EarlyReturn<R, E> {
+        match self {
+            EarlyReturn::Expr(Some(e)) => EarlyReturn::Expr(e),
+            EarlyReturn::Expr(None) => EarlyReturn::Return(none_value),
+            EarlyReturn::Return(r) => EarlyReturn::Return(r),
+        }
+    }
+
+    /// Transpose into an option of early return, an expression value of [None] being [None].
+    pub fn transpose(self) -> Option<EarlyReturn<R, E>> {
+        match self {
+            EarlyReturn::Expr(Some(e)) => Some(EarlyReturn::Expr(e)),
+            EarlyReturn::Expr(None) => None,
+            EarlyReturn::Return(r) => Some(EarlyReturn::Return(r)),
+        }
+    }
+}
+
+impl<R, E, Err> EarlyReturn<R, Result<E, Err>> {
+    /// Transpose into a result of early return, an expression value of [Err] being [Err].
+    pub fn transpose(self) -> Result<EarlyReturn<R, E>, Err> {
+        match self {
+            EarlyReturn::Expr(Ok(e)) => Ok(EarlyReturn::Expr(e)),
+            EarlyReturn::Expr(Err(e)) => Err(e),
+            EarlyReturn::Return(r) => Ok(EarlyReturn::Return(r)),
+        }
+    }
+}
+
 impl<R, E> EarlyReturn<R, EarlyReturn<R, E>> {
     /// Flatten the early return.
     pub fn flatten(self) -> EarlyReturn<R, E> {
@@ -190,6 +326,90 @@ impl<T> EarlyReturn<T, T> {
     }
 }
 
+/// ## IntoEarlyReturn
+///
+/// A value that can be converted into an [EarlyReturn] with the return value `R`.
+/// It is what the steps of [flow!](crate::flow!) are made of.
+pub trait IntoEarlyReturn<R> {
+    /// The expression value.
+    type Expr;
+
+    #[allow(missing_docs)]
+    fn into_early_return(self) -> EarlyReturn<R, Self::Expr>;
+}
+
+impl<R, E> IntoEarlyReturn<R> for EarlyReturn<R, E> {
+    type Expr = E;
+
+    fn into_early_return(self) -> EarlyReturn<R, E> {
+        self
+    }
+}
+
+/// The error is converted with [Into] and returned early, like [EarlyReturn::try_map].
+impl<Succ, Err, T, Err2: Into<Err>> IntoEarlyReturn<Result<Succ, Err>> for Result<T, Err2> {
+    type Expr = T;
+
+    fn into_early_return(self) -> EarlyReturn<Result<Succ, Err>, T> {
+        match self {
+            Ok(t) => EarlyReturn::Expr(t),
+            Err(e) => EarlyReturn::Return(Err(e.into())),
+        }
+    }
+}
+
+// `?` on an [EarlyReturn] returns early with the return value, converted with [From].
+#[cfg(all(feature = "try_trait", kanau_nightly))]
+impl<R, E> std::ops::Try for EarlyReturn<R, E> {
+    type Output = E;
+    type Residual = EarlyReturn<R, Infallible>;
+
+    fn from_output(output: E) -> Self {
+        EarlyReturn::Expr(output)
+    }
+
+    fn branch(self) -> ControlFlow<Self::Residual, E> {
+        match self {
+            EarlyReturn::Expr(e) => ControlFlow::Continue(e),
+            EarlyReturn::Return(r) => ControlFlow::Break(EarlyReturn::Return(r)),
+        }
+    }
+}
+
+#[cfg(all(feature = "try_trait", kanau_nightly))]
+impl<R, E> std::ops::Residual<E> for EarlyReturn<R, Infallible> {
+    type TryType = EarlyReturn<R, E>;
+}
+
+#[cfg(all(feature = "try_trait", kanau_nightly))]
+impl<R: From<R2>, R2, E> std::ops::FromResidual<EarlyReturn<R2, Infallible>> for EarlyReturn<R, E> {
+    fn from_residual(residual: EarlyReturn<R2, Infallible>) -> Self {
+        match residual {
+            EarlyReturn::Return(r) => EarlyReturn::Return(r.into()),
+        }
+    }
+}
+
+// `?` on a [Result] returns early with the error, like [EarlyReturn::from_result].
+#[cfg(all(feature = "try_trait", kanau_nightly))]
+impl<Succ, Err: From<Err2>, Err2, E> std::ops::FromResidual<Result<Infallible, Err2>>
+    for EarlyReturn<Result<Succ, Err>, E>
+{
+    fn from_residual(residual: Result<Infallible, Err2>) -> Self {
+        match residual {
+            Err(e) => EarlyReturn::Return(Err(e.into())),
+        }
+    }
+}
+
+// `?` on an [Option] returns early with [None].
+#[cfg(all(feature = "try_trait", kanau_nightly))]
+impl<T, E> std::ops::FromResidual<Option<Infallible>> for EarlyReturn<Option<T>, E> {
+    fn from_residual(_: Option<Infallible>) -> Self {
+        EarlyReturn::Return(None)
+    }
+}
+
 #[macro_export]
 /// ## early_return
 ///
@@ -218,6 +438,97 @@ macro_rules! monad_early_return {
     };
 }
 
+#[macro_export]
+/// ## flow
+///
+/// Do-notation for [EarlyReturn](crate::flow::EarlyReturn).
+///
+/// - `let x <- step;` binds the expression value of `step`, or makes the whole `flow!`
+///   evaluate to the return value, like [monad_early_return!](crate::monad_early_return!).
+///   The pattern can be an identifier, `mut` identifier, `_`, or a tuple in parentheses.
+/// - `yield value` ends the flow with an expression value.
+/// - A step as the last expression ends the flow with that step.
+/// - Any other statement is kept as is.
+///
+/// A step is anything implementing [IntoEarlyReturn](crate::flow::IntoEarlyReturn):
+/// an [EarlyReturn](crate::flow::EarlyReturn), or a [Result] when the return value is a
+/// [Result], in which case the error returns early like
+/// [EarlyReturn::try_map](crate::flow::EarlyReturn::try_map).
+///
+/// The flow is an expression, it doesn't return from the enclosing function,
+/// and `.await` can be used in the steps.
+///
+/// ```
+/// use kanau::flow::EarlyReturn;
+///
+/// fn half(x: u32) -> EarlyReturn<Result<u32, String>, u32> {
+///     if x.is_multiple_of(2) { EarlyReturn::Expr(x / 2) } else { EarlyReturn::Return(Ok(x)) }
+/// }
+///
+/// fn run(input: &str) -> EarlyReturn<Result<u32, String>, u32> {
+///     kanau::flow! {
+///         let x <- input.parse::<u32>().map_err(|e| e.to_string());
+///         let y <- half(x);
+///         let z = y + 1;
+///         yield z * 10
+///     }
+/// }
+///
+/// assert_eq!(run("4"), EarlyReturn::Expr(30));
+/// assert_eq!(run("3"), EarlyReturn::Return(Ok(3)));
+/// assert!(matches!(run("x"), EarlyReturn::Return(Err(_))));
+/// ```
+macro_rules! flow {
+    (@step $label:lifetime; yield $e:expr $(;)?) => {
+        break $label $crate::flow::EarlyReturn::Expr($e)
+    };
+    (@step $label:lifetime; let $x:ident <- $e:expr; $($rest:tt)*) => {
+        $crate::flow!(@bind $label; ($x) <- $e; $($rest)*)
+    };
+    (@step $label:lifetime; let mut $x:ident <- $e:expr; $($rest:tt)*) => {
+        $crate::flow!(@bind $label; (mut $x) <- $e; $($rest)*)
+    };
+    (@step $label:lifetime; let _ <- $e:expr; $($rest:tt)*) => {
+        $crate::flow!(@bind $label; (_) <- $e; $($rest)*)
+    };
+    (@step $label:lifetime; let ($($p:tt)*) <- $e:expr; $($rest:tt)*) => {
+        $crate::flow!(@bind $label; (($($p)*)) <- $e; $($rest)*)
+    };
+    (@step $label:lifetime; let $x:ident $(: $t:ty)? = $e:expr; $($rest:tt)+) => {{
+        let $x $(: $t)? = $e;
+        $crate::flow!(@step $label; $($rest)+)
+    }};
+    (@step $label:lifetime; let mut $x:ident $(: $t:ty)? = $e:expr; $($rest:tt)+) => {{
+        let mut $x $(: $t)? = $e;
+        $crate::flow!(@step $label; $($rest)+)
+    }};
+    (@step $label:lifetime; let $p:pat = $e:expr; $($rest:tt)+) => {{
+        let $p = $e;
+        $crate::flow!(@step $label; $($rest)+)
+    }};
+    (@step $label:lifetime; $s:expr; $($rest:tt)+) => {{
+        $s;
+        $crate::flow!(@step $label; $($rest)+)
+    }};
+    (@step $label:lifetime; $e:expr $(;)?) => {
+        break $label $crate::flow::IntoEarlyReturn::into_early_return($e)
+    };
+    (@bind $label:lifetime; ($($p:tt)*) <- $e:expr; $($rest:tt)*) => {{
+        let $($p)* = match $crate::flow::IntoEarlyReturn::into_early_return($e) {
+            $crate::flow::EarlyReturn::Expr(e) => e,
+            $crate::flow::EarlyReturn::Return(r) => {
+                break $label $crate::flow::EarlyReturn::Return(r)
+            }
+        };
+        $crate::flow!(@step $label; $($rest)*)
+    }};
+    ($($body:tt)*) => {
+        'flow: {
+            $crate::flow!(@step 'flow; $($body)*)
+        }
+    };
+}
+
 /// ## Continuation Passing Style (CPS)
 ///
 /// A function that takes a processor and a next function,
@@ -254,3 +565,111 @@ pub async fn cps<
     let step1 = monad_early_return!(first.process(input).await);
     rest.process(step1).await
 }
+
+/// ## CpsPipeline
+///
+/// Two processors returning [EarlyReturn] composed with [cps], as a processor.
+///
+/// The pipeline is itself a processor returning [EarlyReturn], so it can be the `rest` of
+/// another pipeline, which is how [cps_pipeline!](crate::cps_pipeline!) chains any number of
+/// steps.
+pub struct CpsPipeline<
+    I,
+    O,
+    Return,
+    Final,
+    P1: Processor<I, EarlyReturn<Return, O>>,
+    P2: Processor<O, EarlyReturn<Return, Final>>,
+> {
+    first: P1,
+    rest: P2,
+    _phantom: PhantomData<(I, O, Return, Final)>,
+}
+
+impl<
+    I,
+    O,
+    Return,
+    Final,
+    P1: Processor<I, EarlyReturn<Return, O>>,
+    P2: Processor<O, EarlyReturn<Return, Final>>,
+> CpsPipeline<I, O, Return, Final, P1, P2>
+{
+    /// Create a new pipeline.
+    pub fn new(first: P1, rest: P2) -> Self {
+        Self {
+            first,
+            rest,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<
+    I,
+    O,
+    Return,
+    Final,
+    P1: Processor<I, EarlyReturn<Return, O>> + Clone,
+    P2: Processor<O, EarlyReturn<Return, Final>> + Clone,
+> Clone for CpsPipeline<I, O, Return, Final, P1, P2>
+{
+    fn clone(&self) -> Self {
+        Self::new(self.first.clone(), self.rest.clone())
+    }
+}
+
+impl<
+    I,
+    O,
+    Return,
+    Final,
+    P1: Processor<I, EarlyReturn<Return, O>> + Debug,
+    P2: Processor<O, EarlyReturn<Return, Final>> + Debug,
+> Debug for CpsPipeline<I, O, Return, Final, P1, P2>
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CpsPipeline")
+            .field("first", &self.first)
+            .field("rest", &self.rest)
+            .finish()
+    }
+}
+
+impl<
+    I: Send + Sync,
+    O: Send + Sync,
+    Return: Send + Sync,
+    Final: Send + Sync,
+    P1: Processor<I, EarlyReturn<Return, O>> + Send + Sync,
+    P2: Processor<O, EarlyReturn<Return, Final>> + Send + Sync,
+> Processor<I, EarlyReturn<Return, Final>> for CpsPipeline<I, O, Return, Final, P1, P2>
+{
+    async fn process(&self, input: I) -> EarlyReturn<Return, Final> {
+        cps(&self.first, &self.rest, input).await
+    }
+}
+
+#[macro_export]
+/// ## cps_pipeline
+///
+/// Chain any number of processors returning [EarlyReturn](crate::flow::EarlyReturn) into a
+/// single processor.
+///
+/// The output of every step is the input of the next one, and the first
+/// [EarlyReturn::Return](crate::flow::EarlyReturn::Return) stops the pipeline.
+/// The steps are nested [CpsPipeline](crate::flow::CpsPipeline)s, so a pipeline can be a step
+/// of another pipeline, or be wrapped by a layer.
+///
+/// ```ignore
+/// let pipeline = kanau::cps_pipeline!(authenticate, load_user, check_permission, handle);
+/// let response = pipeline.process(request).await;
+/// ```
+macro_rules! cps_pipeline {
+    ($first:expr $(,)?) => {
+        $first
+    };
+    ($first:expr, $($rest:expr),+ $(,)?) => {
+        $crate::flow::CpsPipeline::new($first, $crate::cps_pipeline!($($rest),+))
+    };
+}
@@ -0,0 +1,199 @@
+use crate::flow::{EarlyReturn, IntoEarlyReturn};
+use crate::processor::Processor;
+use futures::StreamExt;
+use futures::stream;
+
+/// ## Validation
+///
+/// The result of a validation that keeps all the errors.
+///
+/// Unlike [EarlyReturn] or [Result], combining two invalid values with [Validation::zip] or
+/// [Validation::and] keeps the errors of both, so every field of a form can be checked at once.
+/// An invalid value usually holds at least one error, but `Invalid(vec![])` is still invalid.
+///
+/// ```
+/// use kanau::flow::{EarlyReturn, Validation};
+///
+/// fn non_empty(field: &'static str, value: &str) -> Validation<String, String> {
+///     if value.is_empty() {
+///         Validation::invalid(format!("{field} is empty"))
+///     } else {
+///         Validation::valid(value.to_string())
+///     }
+/// }
+///
+/// let user = non_empty("name", "").zip(non_empty("email", ""));
+/// assert_eq!(
+///     user.into_early_return(),
+///     EarlyReturn::Return(vec!["name is empty".to_string(), "email is empty".to_string()])
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Validation<E, T> {
+    /// The value is valid.
+    Valid(T),
+
+    /// The value is invalid, with all the errors found.
+    Invalid(Vec<E>),
+}
+
+impl<E, T> Validation<E, T> {
+    /// Create a [Validation::Valid].
+    pub fn valid(t: T) -> Self {
+        Validation::Valid(t)
+    }
+
+    /// Create a [Validation::Invalid] with a single error.
+    pub fn invalid(e: E) -> Self {
+        Validation::Invalid(vec![e])
+    }
+
+    /// Check whether the value is valid.
+    pub fn is_valid(&self) -> bool {
+        matches!(self, Validation::Valid(_))
+    }
+
+    /// Map the valid value.
+    pub fn map<F: FnOnce(T) -> U, U>(self, f: F) -> Validation<E, U> {
+        match self {
+            Validation::Valid(t) => Validation::Valid(f(t)),
+            Validation::Invalid(errors) => Validation::Invalid(errors),
+        }
+    }
+
+    /// Map every error.
+    pub fn map_err<F: FnMut(E) -> E2, E2>(self, f: F) -> Validation<E2, T> {
+        match self {
+            Validation::Valid(t) => Validation::Valid(t),
+            Validation::Invalid(errors) => Validation::Invalid(errors.into_iter().map(f).collect()),
+        }
+    }
+
+    /// Map the valid value with an async function.
+    pub async fn process_map<P: Processor<T, U>, U>(self, processor: &P) -> Validation<E, U> {
+        match self {
+            Validation::Valid(t) => Validation::Valid(processor.process(t).await),
+            Validation::Invalid(errors) => Validation::Invalid(errors),
+        }
+    }
+
+    /// Combine the valid values of both into a pair, or keep the errors of both.
+    pub fn zip<U>(self, other: Validation<E, U>) -> Validation<E, (T, U)> {
+        self.zip_with(other, |t, u| (t, u))
+    }
+
+    /// Combine the valid values of both with a function, or keep the errors of both.
+    pub fn zip_with<U, V, F: FnOnce(T, U) -> V>(
+        self,
+        other: Validation<E, U>,
+        f: F,
+    ) -> Validation<E, V> {
+        match (self, other) {
+            (Validation::Valid(t), Validation::Valid(u)) => Validation::Valid(f(t, u)),
+            (Validation::Valid(_), Validation::Invalid(errors))
+            | (Validation::Invalid(errors), Validation::Valid(_)) => Validation::Invalid(errors),
+            (Validation::Invalid(mut errors), Validation::Invalid(others)) => {
+                errors.extend(others);
+                Validation::Invalid(errors)
+            }
+        }
+    }
+
+    /// Get the second value if both are valid, or keep the errors of both.
+    pub fn and<U>(self, other: Validation<E, U>) -> Validation<E, U> {
+        self.zip_with(other, |_, u| u)
+    }
+
+    /// Validate every item of an iterator, collecting the valid values, or all the errors.
+    pub fn traverse<Iter: IntoIterator, F: FnMut(Iter::Item) -> Validation<E, T>>(
+        iter: Iter,
+        f: F,
+    ) -> Validation<E, Vec<T>> {
+        iter.into_iter().map(f).collect()
+    }
+
+    /// Validate every item of an iterator with a processor, concurrently, collecting the valid
+    /// values in order, or all the errors.
+    ///
+    /// At most `limit` items are validated at the same time, `0` is treated as `1`.
+    pub async fn process_traverse<
+        Iter: IntoIterator,
+        P: Processor<Iter::Item, Validation<E, T>>,
+    >(
+        iter: Iter,
+        processor: &P,
+        limit: usize,
+    ) -> Validation<E, Vec<T>> {
+        stream::iter(iter)
+            .map(|item| processor.process(item))
+            .buffered(limit.max(1))
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect()
+    }
+
+    /// Convert into a [Result].
+    pub fn into_result(self) -> Result<T, Vec<E>> {
+        match self {
+            Validation::Valid(t) => Ok(t),
+            Validation::Invalid(errors) => Err(errors),
+        }
+    }
+
+    /// Convert into an [EarlyReturn], returning early with the errors.
+    pub fn into_early_return(self) -> EarlyReturn<Vec<E>, T> {
+        self.into()
+    }
+}
+
+impl<E, T> From<Result<T, E>> for Validation<E, T> {
+    fn from(value: Result<T, E>) -> Self {
+        match value {
+            Ok(t) => Validation::Valid(t),
+            Err(e) => Validation::invalid(e),
+        }
+    }
+}
+
+impl<E, T> From<Validation<E, T>> for EarlyReturn<Vec<E>, T> {
+    fn from(value: Validation<E, T>) -> Self {
+        match value {
+            Validation::Valid(t) => EarlyReturn::Expr(t),
+            Validation::Invalid(errors) => EarlyReturn::Return(errors),
+        }
+    }
+}
+
+impl<E, T> IntoEarlyReturn<Vec<E>> for Validation<E, T> {
+    type Expr = T;
+
+    fn into_early_return(self) -> EarlyReturn<Vec<E>, T> {
+        self.into()
+    }
+}
+
+/// Collect the valid values, or all the errors.
+impl<E, T, V: FromIterator<T>> FromIterator<Validation<E, T>> for Validation<E, V> {
+    fn from_iter<Iter: IntoIterator<Item = Validation<E, T>>>(iter: Iter) -> Self {
+        // `Invalid(vec![])` is still invalid
+        let mut invalid = false;
+        let mut errors = Vec::new();
+        let values = iter
+            .into_iter()
+            .filter_map(|item| match item {
+                Validation::Valid(t) => Some(t),
+                Validation::Invalid(e) => {
+                    invalid = true;
+                    errors.extend(e);
+                    None
+                }
+            })
+            .collect();
+        if !invalid {
+            Validation::Valid(values)
+        } else {
+            Validation::Invalid(errors)
+        }
+    }
+}
@@ -2,6 +2,21 @@ use crate::processor::Processor;
 use std::fmt::Debug;
 use std::marker::PhantomData;
 
+/// Stop calling failing processors.
+pub mod circuit_breaker;
+
+/// Concurrency and rate limits for processors.
+pub mod limit;
+
+/// Retry failed processors.
+pub mod retry;
+
+/// Compose layers.
+pub mod stack;
+
+/// Timeout for processors.
+pub mod timeout;
+
 /// ## Layer
 ///
 /// A Layer is a crucial component in kanau that wraps around a Processor,
@@ -13,6 +28,72 @@ pub trait Layer<I, O, P: Processor<I, O>> {
     fn wrap(&self, processor: &P, input: I) -> impl Future<Output = O> + Send;
 }
 
+/// ## ProcessorLayer
+///
+/// A layer that takes a processor and produces a new processor, like `tower::Layer`.
+///
+/// Unlike [Layer], the produced processor is not tied to the input and output types of the
+/// wrapped processor, so a [ProcessorLayer] can change them, e.g. turn a `Processor<I, O>` into a
+/// `Processor<I, Result<O, E>>`. It can also be generic over the wrapped processor without
+/// naming its input and output types.
+///
+/// Every [Layer] that is [Clone] is also a [ProcessorLayer], producing a [LayerWrappedProcessor].
+/// The `Marker` parameter only tells these two kinds of implementations apart, it is inferred
+/// at the call site. Generic code accepting both kinds should take `Marker` as a type parameter.
+pub trait ProcessorLayer<P, Marker = ()> {
+    /// The processor produced by this layer.
+    type Wrapped;
+
+    /// Wrap a processor with this layer.
+    fn layer(&self, inner: P) -> Self::Wrapped;
+}
+
+/// ## FromLayer
+///
+/// The `Marker` of the [ProcessorLayer] implementation for every [Layer].
+pub struct FromLayer<I, O> {
+    _phantom: PhantomData<(I, O)>,
+}
+
+impl<I, O, P: Processor<I, O>, L: Layer<I, O, P> + Clone> ProcessorLayer<P, FromLayer<I, O>> for L {
+    type Wrapped = LayerWrappedProcessor<I, O, P, L>;
+
+    fn layer(&self, inner: P) -> Self::Wrapped {
+        LayerWrappedProcessor::new(self.clone(), inner)
+    }
+}
+
+/// ## LayerFn
+///
+/// A [ProcessorLayer] made of a function that takes a processor and returns a new processor.
+#[derive(Clone, Copy)]
+pub struct LayerFn<F> {
+    f: F,
+}
+
+impl<F> LayerFn<F> {
+    /// Create a new layer from a function.
+    pub fn new(f: F) -> Self {
+        Self { f }
+    }
+}
+
+impl<F> Debug for LayerFn<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LayerFn")
+            .field("f", &"Fn(P) -> Wrapped")
+            .finish()
+    }
+}
+
+impl<P, Wrapped, F: Fn(P) -> Wrapped> ProcessorLayer<P> for LayerFn<F> {
+    type Wrapped = Wrapped;
+
+    fn layer(&self, inner: P) -> Self::Wrapped {
+        (self.f)(inner)
+    }
+}
+
 /// ## Adapter
 ///
 /// Convert types from I1 to I2 and from O2 to O1.
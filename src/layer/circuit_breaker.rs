@@ -0,0 +1,341 @@
+use crate::layer::Layer;
+use crate::processor::Processor;
+use std::collections::VecDeque;
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+use thiserror::Error;
+use tokio::time::Instant;
+
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq)]
+#[error("Circuit breaker is open")]
+/// Error returned by a processor wrapped with [CircuitBreakerLayer] while the circuit is open.
+pub struct CircuitOpenError;
+
+/// ## CircuitState
+///
+/// The state of a [CircuitBreakerLayer].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Calls pass through, the outcomes are recorded.
+    Closed,
+
+    /// Calls are rejected with [CircuitOpenError] without calling the wrapped processor.
+    Open,
+
+    /// A limited number of trial calls pass through to decide whether the circuit should close again.
+    HalfOpen,
+}
+
+type StateChangeCallback = Arc<dyn Fn(CircuitState, CircuitState) + Send + Sync>;
+
+#[derive(Debug, Clone, Copy)]
+struct CircuitConfig {
+    failure_rate_threshold: f64,
+    window_size: usize,
+    minimum_calls: usize,
+    open_duration: Duration,
+    half_open_calls: usize,
+}
+
+struct CircuitInner {
+    state: CircuitState,
+    // `true` for a failed call, only the last `window_size` outcomes are kept
+    window: VecDeque<bool>,
+    opened_at: Instant,
+    half_open_in_flight: usize,
+    half_open_successes: usize,
+}
+
+struct CircuitShared {
+    config: CircuitConfig,
+    inner: Mutex<CircuitInner>,
+    on_state_change: Option<StateChangeCallback>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Permit {
+    Closed,
+    Trial,
+}
+
+impl CircuitShared {
+    fn lock(&self) -> MutexGuard<'_, CircuitInner> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn notify(&self, transition: Option<(CircuitState, CircuitState)>) {
+        if let (Some((from, to)), Some(callback)) = (transition, &self.on_state_change) {
+            callback(from, to);
+        }
+    }
+
+    fn acquire(&self) -> Option<Permit> {
+        let mut transition = None;
+        let permit = {
+            let mut inner = self.lock();
+            if inner.state == CircuitState::Open
+                && inner.opened_at.elapsed() >= self.config.open_duration
+            {
+                transition = inner.transit(CircuitState::HalfOpen);
+            }
+            match inner.state {
+                CircuitState::Closed => Some(Permit::Closed),
+                CircuitState::Open => None,
+                CircuitState::HalfOpen => {
+                    if inner.half_open_in_flight < self.config.half_open_calls {
+                        inner.half_open_in_flight += 1;
+                        Some(Permit::Trial)
+                    } else {
+                        None
+                    }
+                }
+            }
+        };
+        self.notify(transition);
+        permit
+    }
+
+    fn record(&self, permit: Permit, failed: bool) {
+        let transition = {
+            let mut inner = self.lock();
+            match (permit, inner.state) {
+                (Permit::Closed, CircuitState::Closed) => {
+                    inner.window.push_back(failed);
+                    if inner.window.len() > self.config.window_size {
+                        inner.window.pop_front();
+                    }
+                    let failures = inner.window.iter().filter(|failed| **failed).count();
+                    let calls = inner.window.len();
+                    // a threshold of `0.0` still needs a failure to open the circuit
+                    if calls >= self.config.minimum_calls
+                        && failures > 0
+                        && failures as f64 >= self.config.failure_rate_threshold * calls as f64
+                    {
+                        inner.transit(CircuitState::Open)
+                    } else {
+                        None
+                    }
+                }
+                (Permit::Trial, CircuitState::HalfOpen) => {
+                    inner.half_open_in_flight = inner.half_open_in_flight.saturating_sub(1);
+                    if failed {
+                        inner.transit(CircuitState::Open)
+                    } else {
+                        inner.half_open_successes += 1;
+                        if inner.half_open_successes >= self.config.half_open_calls {
+                            inner.transit(CircuitState::Closed)
+                        } else {
+                            None
+                        }
+                    }
+                }
+                // the state has changed since the call started, the outcome is outdated
+                _ => None,
+            }
+        };
+        self.notify(transition);
+    }
+
+    fn cancel(&self, permit: Permit) {
+        if permit == Permit::Trial {
+            let mut inner = self.lock();
+            if inner.state == CircuitState::HalfOpen {
+                inner.half_open_in_flight = inner.half_open_in_flight.saturating_sub(1);
+            }
+        }
+    }
+}
+
+impl CircuitInner {
+    fn transit(&mut self, to: CircuitState) -> Option<(CircuitState, CircuitState)> {
+        let from = self.state;
+        if from == to {
+            return None;
+        }
+        self.state = to;
+        self.window.clear();
+        self.half_open_in_flight = 0;
+        self.half_open_successes = 0;
+        if to == CircuitState::Open {
+            self.opened_at = Instant::now();
+        }
+        Some((from, to))
+    }
+}
+
+// release the half-open slot if the future is dropped before the wrapped processor finishes
+struct PermitGuard<'a> {
+    shared: &'a CircuitShared,
+    permit: Option<Permit>,
+}
+
+impl PermitGuard<'_> {
+    fn finish(mut self, failed: bool) {
+        if let Some(permit) = self.permit.take() {
+            self.shared.record(permit, failed);
+        }
+    }
+}
+
+impl Drop for PermitGuard<'_> {
+    fn drop(&mut self) {
+        if let Some(permit) = self.permit.take() {
+            self.shared.cancel(permit);
+        }
+    }
+}
+
+/// ## CircuitBreakerConfig
+///
+/// The configuration of a [CircuitBreakerLayer].
+///
+/// Every call to [CircuitBreakerConfig::build] creates a new circuit.
+#[derive(Clone)]
+pub struct CircuitBreakerConfig {
+    config: CircuitConfig,
+    on_state_change: Option<StateChangeCallback>,
+}
+
+impl CircuitBreakerConfig {
+    /// Create a new circuit breaker configuration.
+    ///
+    /// `failure_rate_threshold` is clamped between `0.0` and `1.0`, NaN is treated as `1.0`.
+    /// At least one failure is needed to open the circuit. By default, `minimum_calls` equals
+    /// `window_size` and one trial call is allowed while half-open.
+    pub fn new(failure_rate_threshold: f64, window_size: usize, open_duration: Duration) -> Self {
+        let failure_rate_threshold = if failure_rate_threshold.is_nan() {
+            1.0
+        } else {
+            failure_rate_threshold.clamp(0.0, 1.0)
+        };
+        Self {
+            config: CircuitConfig {
+                failure_rate_threshold,
+                window_size: window_size.max(1),
+                minimum_calls: window_size.max(1),
+                open_duration,
+                half_open_calls: 1,
+            },
+            on_state_change: None,
+        }
+    }
+
+    /// Set the number of recorded calls needed before the circuit can open.
+    ///
+    /// It is clamped between `1` and `window_size`.
+    pub fn with_minimum_calls(self, minimum_calls: usize) -> Self {
+        Self {
+            config: CircuitConfig {
+                minimum_calls: minimum_calls.clamp(1, self.config.window_size),
+                ..self.config
+            },
+            ..self
+        }
+    }
+
+    /// Set the number of trial calls while half-open.
+    pub fn with_half_open_calls(self, half_open_calls: usize) -> Self {
+        Self {
+            config: CircuitConfig {
+                half_open_calls: half_open_calls.max(1),
+                ..self.config
+            },
+            ..self
+        }
+    }
+
+    /// Set a callback called with the old and new state whenever the state changes.
+    pub fn on_state_change<F: Fn(CircuitState, CircuitState) + Send + Sync + 'static>(
+        self,
+        callback: F,
+    ) -> Self {
+        Self {
+            on_state_change: Some(Arc::new(callback)),
+            ..self
+        }
+    }
+
+    /// Create a layer with a new circuit.
+    pub fn build(self) -> CircuitBreakerLayer {
+        CircuitBreakerLayer {
+            shared: Arc::new(CircuitShared {
+                config: self.config,
+                inner: Mutex::new(CircuitInner {
+                    state: CircuitState::Closed,
+                    window: VecDeque::with_capacity(self.config.window_size),
+                    opened_at: Instant::now(),
+                    half_open_in_flight: 0,
+                    half_open_successes: 0,
+                }),
+                on_state_change: self.on_state_change,
+            }),
+        }
+    }
+}
+
+impl Debug for CircuitBreakerConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CircuitBreakerConfig")
+            .field("config", &self.config)
+            .field("on_state_change", &self.on_state_change.is_some())
+            .finish()
+    }
+}
+
+/// ## CircuitBreakerLayer
+///
+/// A layer that stops calling a failing processor for a while.
+///
+/// - While **closed**, the outcomes of the last `window_size` calls are recorded. Once at least
+///   `minimum_calls` are recorded and the failure rate reaches `failure_rate_threshold`,
+///   the circuit opens.
+/// - While **open**, calls fail immediately with [CircuitOpenError], converted into the error type
+///   of the wrapped processor with [From]. After `open_duration`, the circuit becomes half-open.
+/// - While **half-open**, only `half_open_calls` trial calls pass through. If all of them succeed,
+///   the circuit closes, if any of them fails, the circuit opens again.
+///
+/// The state is stored behind an [Arc], clones of the layer share the same circuit.
+/// Use [CircuitBreakerConfig] to set the other options.
+#[derive(Clone)]
+pub struct CircuitBreakerLayer {
+    shared: Arc<CircuitShared>,
+}
+
+impl CircuitBreakerLayer {
+    /// Create a new circuit breaker layer with the default options of [CircuitBreakerConfig].
+    pub fn new(failure_rate_threshold: f64, window_size: usize, open_duration: Duration) -> Self {
+        CircuitBreakerConfig::new(failure_rate_threshold, window_size, open_duration).build()
+    }
+
+    /// Get the current state of the circuit.
+    pub fn state(&self) -> CircuitState {
+        self.shared.lock().state
+    }
+}
+
+impl Debug for CircuitBreakerLayer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CircuitBreakerLayer")
+            .field("config", &self.shared.config)
+            .field("state", &self.state())
+            .finish()
+    }
+}
+
+impl<I: Send, O: Send, E: From<CircuitOpenError> + Send, P: Processor<I, Result<O, E>> + Sync>
+    Layer<I, Result<O, E>, P> for CircuitBreakerLayer
+{
+    async fn wrap(&self, processor: &P, input: I) -> Result<O, E> {
+        let Some(permit) = self.shared.acquire() else {
+            return Err(CircuitOpenError.into());
+        };
+        let guard = PermitGuard {
+            shared: &self.shared,
+            permit: Some(permit),
+        };
+        let result = processor.process(input).await;
+        guard.finish(result.is_err());
+        result
+    }
+}
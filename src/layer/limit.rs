@@ -0,0 +1,161 @@
+use crate::layer::Layer;
+use crate::processor::Processor;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+use thiserror::Error;
+use tokio::sync::{Semaphore, TryAcquireError};
+use tokio::time::Instant;
+
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq)]
+#[error("Concurrency limit exceeded")]
+/// Error returned by a processor wrapped with [RejectingConcurrencyLimitLayer] when there is no
+/// permit left.
+pub struct ConcurrencyLimitError;
+
+/// ## ConcurrencyLimitLayer
+///
+/// A layer that limits how many calls of the wrapped processor run at the same time.
+/// Extra calls wait until a running one finishes.
+///
+/// Clones of the layer share the same limit, so several processors can draw from one budget.
+#[derive(Debug, Clone)]
+pub struct ConcurrencyLimitLayer {
+    semaphore: Arc<Semaphore>,
+}
+
+impl ConcurrencyLimitLayer {
+    /// Create a new concurrency limit layer. A `limit` of `0` is treated as `1`.
+    pub fn new(limit: usize) -> Self {
+        Self::from_semaphore(Arc::new(Semaphore::new(limit.max(1))))
+    }
+
+    /// Create a new concurrency limit layer from an existing semaphore.
+    ///
+    /// Closing the semaphore lifts the limit, the calls then run without waiting for a permit.
+    pub fn from_semaphore(semaphore: Arc<Semaphore>) -> Self {
+        Self { semaphore }
+    }
+
+    /// Reject the extra calls with [ConcurrencyLimitError] instead of waiting.
+    ///
+    /// The returned layer shares the same limit with this layer.
+    pub fn rejecting(self) -> RejectingConcurrencyLimitLayer {
+        RejectingConcurrencyLimitLayer {
+            semaphore: self.semaphore,
+        }
+    }
+
+    /// Get the number of permits currently available.
+    pub fn available_permits(&self) -> usize {
+        self.semaphore.available_permits()
+    }
+}
+
+impl<I: Send, O: Send, P: Processor<I, O> + Sync> Layer<I, O, P> for ConcurrencyLimitLayer {
+    async fn wrap(&self, processor: &P, input: I) -> O {
+        // acquiring only fails if a user-provided semaphore is closed, the limit is lifted then
+        let _permit = self.semaphore.acquire().await.ok();
+        processor.process(input).await
+    }
+}
+
+/// ## RejectingConcurrencyLimitLayer
+///
+/// Same as [ConcurrencyLimitLayer], but the extra calls fail immediately with
+/// [ConcurrencyLimitError], converted into the error type of the wrapped processor with [From].
+#[derive(Debug, Clone)]
+pub struct RejectingConcurrencyLimitLayer {
+    semaphore: Arc<Semaphore>,
+}
+
+impl RejectingConcurrencyLimitLayer {
+    /// Create a new rejecting concurrency limit layer. A `limit` of `0` is treated as `1`.
+    pub fn new(limit: usize) -> Self {
+        ConcurrencyLimitLayer::new(limit).rejecting()
+    }
+
+    /// Get the number of permits currently available.
+    pub fn available_permits(&self) -> usize {
+        self.semaphore.available_permits()
+    }
+}
+
+impl<I: Send, O: Send, E: From<ConcurrencyLimitError> + Send, P: Processor<I, Result<O, E>> + Sync>
+    Layer<I, Result<O, E>, P> for RejectingConcurrencyLimitLayer
+{
+    async fn wrap(&self, processor: &P, input: I) -> Result<O, E> {
+        // like [ConcurrencyLimitLayer], a closed semaphore lifts the limit
+        let _permit = match self.semaphore.try_acquire() {
+            Ok(permit) => Some(permit),
+            Err(TryAcquireError::Closed) => None,
+            Err(TryAcquireError::NoPermits) => return Err(ConcurrencyLimitError.into()),
+        };
+        processor.process(input).await
+    }
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+/// ## RateLimitLayer
+///
+/// A layer that limits how often the wrapped processor is called, with a token bucket.
+///
+/// The bucket holds at most `burst` tokens and is refilled with `rate` tokens every `per`.
+/// Every call takes one token, and waits until one is available if the bucket is empty.
+///
+/// Clones of the layer share the same bucket, so several processors can draw from one quota.
+#[derive(Debug, Clone)]
+pub struct RateLimitLayer {
+    bucket: Arc<Mutex<TokenBucket>>,
+    burst: f64,
+    // tokens per second
+    refill_rate: f64,
+}
+
+impl RateLimitLayer {
+    /// Create a new rate limit layer. The bucket starts full.
+    pub fn new(burst: u32, rate: u32, per: Duration) -> Self {
+        let burst = f64::from(burst.max(1));
+        Self {
+            bucket: Arc::new(Mutex::new(TokenBucket {
+                tokens: burst,
+                last_refill: Instant::now(),
+            })),
+            burst,
+            refill_rate: f64::from(rate) / per.as_secs_f64(),
+        }
+    }
+
+    // take a token, or return how long to wait before trying again
+    fn try_take(&self) -> Result<(), Duration> {
+        let mut bucket = self.bucket.lock().unwrap_or_else(PoisonError::into_inner);
+        let now = Instant::now();
+        let refilled = now.duration_since(bucket.last_refill).as_secs_f64() * self.refill_rate;
+        bucket.tokens = (bucket.tokens + refilled).min(self.burst);
+        bucket.last_refill = now;
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            return Ok(());
+        }
+        let wait = (1.0 - bucket.tokens) / self.refill_rate;
+        Err(Duration::try_from_secs_f64(wait).unwrap_or(Duration::MAX))
+    }
+
+    /// Wait until a token is available and take it.
+    pub async fn acquire(&self) {
+        while let Err(wait) = self.try_take() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+impl<I: Send, O: Send, P: Processor<I, O> + Sync> Layer<I, O, P> for RateLimitLayer {
+    async fn wrap(&self, processor: &P, input: I) -> O {
+        self.acquire().await;
+        processor.process(input).await
+    }
+}
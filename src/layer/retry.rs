@@ -0,0 +1,249 @@
+use crate::layer::Layer;
+use crate::processor::Processor;
+use std::collections::hash_map::RandomState;
+use std::fmt::Debug;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// ## RetryState
+///
+/// The state of a retrying processor, passed to [RetryPolicy].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryState {
+    /// How many attempts have failed, including the current one. Starts at `1`.
+    pub attempt: u32,
+
+    /// Time passed since the first attempt started.
+    pub elapsed: Duration,
+}
+
+/// ## RetryPolicy
+///
+/// Decide whether a failed attempt should be retried, and how long to wait before the next one.
+pub trait RetryPolicy<E> {
+    /// Return the delay before the next attempt, or `None` to give up and return the error.
+    fn next_delay(&self, error: &E, state: &RetryState) -> Option<Duration>;
+}
+
+/// ## FixedDelay
+///
+/// Retry forever, waiting the same duration between attempts.
+///
+/// Usually combined with [MaxAttempts] or [MaxElapsed].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixedDelay {
+    delay: Duration,
+}
+
+impl FixedDelay {
+    /// Create a new fixed delay policy.
+    pub fn new(delay: Duration) -> Self {
+        Self { delay }
+    }
+}
+
+impl<E> RetryPolicy<E> for FixedDelay {
+    fn next_delay(&self, _error: &E, _state: &RetryState) -> Option<Duration> {
+        Some(self.delay)
+    }
+}
+
+/// ## ExponentialBackoff
+///
+/// Retry forever, the delay is multiplied by `multiplier` after every attempt
+/// and capped at `max_delay`.
+///
+/// With jitter enabled, the actual delay is a random duration between zero and the computed delay,
+/// so the clients failing at the same time don't retry at the same time.
+///
+/// Usually combined with [MaxAttempts] or [MaxElapsed].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ExponentialBackoff {
+    initial_delay: Duration,
+    multiplier: f64,
+    max_delay: Duration,
+    jitter: bool,
+}
+
+impl ExponentialBackoff {
+    /// Create a new exponential backoff policy that doubles the delay after every attempt.
+    pub fn new(initial_delay: Duration, max_delay: Duration) -> Self {
+        Self {
+            initial_delay,
+            multiplier: 2.0,
+            max_delay,
+            jitter: false,
+        }
+    }
+
+    /// Set the multiplier of the delay.
+    pub fn with_multiplier(self, multiplier: f64) -> Self {
+        Self { multiplier, ..self }
+    }
+
+    /// Enable or disable jitter.
+    pub fn with_jitter(self, jitter: bool) -> Self {
+        Self { jitter, ..self }
+    }
+
+    fn delay_of(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(i32::MAX as u32) as i32;
+        let delay = self.initial_delay.as_secs_f64() * self.multiplier.powi(exponent);
+        let delay = Duration::try_from_secs_f64(delay)
+            .unwrap_or(self.max_delay)
+            .min(self.max_delay);
+        if self.jitter {
+            let random = RandomState::new().build_hasher().finish();
+            // `mul_f64` panics when the rounded product overflows, near `Duration::MAX`
+            let ratio = random as f64 / u64::MAX as f64;
+            Duration::try_from_secs_f64(delay.as_secs_f64() * ratio).map_or(delay, |d| d.min(delay))
+        } else {
+            delay
+        }
+    }
+}
+
+impl<E> RetryPolicy<E> for ExponentialBackoff {
+    fn next_delay(&self, _error: &E, state: &RetryState) -> Option<Duration> {
+        Some(self.delay_of(state.attempt))
+    }
+}
+
+/// ## MaxAttempts
+///
+/// Give up after a number of attempts in total.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MaxAttempts<P> {
+    policy: P,
+    max: u32,
+}
+
+impl<P> MaxAttempts<P> {
+    /// Create a new max attempts policy.
+    pub fn new(policy: P, max: u32) -> Self {
+        Self { policy, max }
+    }
+}
+
+impl<E, P: RetryPolicy<E>> RetryPolicy<E> for MaxAttempts<P> {
+    fn next_delay(&self, error: &E, state: &RetryState) -> Option<Duration> {
+        if state.attempt >= self.max {
+            return None;
+        }
+        self.policy.next_delay(error, state)
+    }
+}
+
+/// ## MaxElapsed
+///
+/// Give up if the next attempt would start after a duration since the first attempt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MaxElapsed<P> {
+    policy: P,
+    max: Duration,
+}
+
+impl<P> MaxElapsed<P> {
+    /// Create a new max elapsed time policy.
+    pub fn new(policy: P, max: Duration) -> Self {
+        Self { policy, max }
+    }
+}
+
+impl<E, P: RetryPolicy<E>> RetryPolicy<E> for MaxElapsed<P> {
+    fn next_delay(&self, error: &E, state: &RetryState) -> Option<Duration> {
+        let delay = self.policy.next_delay(error, state)?;
+        // a delay too long to add is past any maximum
+        if state
+            .elapsed
+            .checked_add(delay)
+            .is_none_or(|elapsed| elapsed > self.max)
+        {
+            return None;
+        }
+        Some(delay)
+    }
+}
+
+/// ## RetryIf
+///
+/// Only retry the errors that the predicate returns `true` for.
+#[derive(Clone)]
+pub struct RetryIf<P, F> {
+    policy: P,
+    predicate: F,
+}
+
+impl<P, F> RetryIf<P, F> {
+    /// Create a new conditional retry policy.
+    pub fn new(policy: P, predicate: F) -> Self {
+        Self { policy, predicate }
+    }
+}
+
+impl<P: Debug, F> Debug for RetryIf<P, F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RetryIf")
+            .field("policy", &self.policy)
+            .field("predicate", &"Fn(&E) -> bool")
+            .finish()
+    }
+}
+
+impl<E, P: RetryPolicy<E>, F: Fn(&E) -> bool> RetryPolicy<E> for RetryIf<P, F> {
+    fn next_delay(&self, error: &E, state: &RetryState) -> Option<Duration> {
+        if !(self.predicate)(error) {
+            return None;
+        }
+        self.policy.next_delay(error, state)
+    }
+}
+
+/// ## RetryLayer
+///
+/// A layer that calls the wrapped processor again when it returns an error,
+/// as long as the [RetryPolicy] allows.
+///
+/// The input is cloned for every attempt. The delays are measured with [tokio::time],
+/// so they respect a paused clock in tests.
+#[derive(Debug, Clone)]
+pub struct RetryLayer<R> {
+    policy: R,
+}
+
+impl<R> RetryLayer<R> {
+    /// Create a new retry layer.
+    pub fn new(policy: R) -> Self {
+        Self { policy }
+    }
+}
+
+impl<
+    I: Clone + Send + Sync,
+    O: Send + Sync,
+    E: Send + Sync,
+    P: Processor<I, Result<O, E>> + Sync,
+    R: RetryPolicy<E> + Sync,
+> Layer<I, Result<O, E>, P> for RetryLayer<R>
+{
+    async fn wrap(&self, processor: &P, input: I) -> Result<O, E> {
+        let start = Instant::now();
+        let mut attempt: u32 = 0;
+        loop {
+            attempt = attempt.saturating_add(1);
+            let error = match processor.process(input.clone()).await {
+                Ok(output) => return Ok(output),
+                Err(error) => error,
+            };
+            let state = RetryState {
+                attempt,
+                elapsed: start.elapsed(),
+            };
+            match self.policy.next_delay(&error, &state) {
+                Some(delay) => tokio::time::sleep(delay).await,
+                None => return Err(error),
+            }
+        }
+    }
+}
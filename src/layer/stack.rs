@@ -0,0 +1,120 @@
+use crate::layer::{Layer, LayerWrappedProcessor};
+use crate::processor::Processor;
+use std::fmt::Debug;
+
+/// ## Identity
+///
+/// A layer that does nothing, the input is passed to the processor as is.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Identity;
+
+impl<I: Send, O, P: Processor<I, O> + Sync> Layer<I, O, P> for Identity {
+    fn wrap(&self, processor: &P, input: I) -> impl Future<Output = O> + Send {
+        processor.process(input)
+    }
+}
+
+/// ## Stack
+///
+/// Two layers composed into one.
+///
+/// The `Outer` layer receives the input first, and the processor it wraps is the `Inner` layer
+/// wrapping the actual processor. So the call goes `Outer -> Inner -> processor`,
+/// and the output goes back `processor -> Inner -> Outer`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Stack<Outer, Inner> {
+    outer: Outer,
+    inner: Inner,
+}
+
+impl<Outer, Inner> Stack<Outer, Inner> {
+    /// Create a new stack of layers.
+    pub fn new(outer: Outer, inner: Inner) -> Self {
+        Self { outer, inner }
+    }
+}
+
+/// ## StackedProcessor
+///
+/// A borrowed processor wrapped by the inner layer of a [Stack],
+/// it is the processor that the outer layer wraps.
+pub struct StackedProcessor<'a, L, P> {
+    layer: &'a L,
+    processor: &'a P,
+}
+
+impl<L: Debug, P: Debug> Debug for StackedProcessor<'_, L, P> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StackedProcessor")
+            .field("layer", &self.layer)
+            .field("processor", &self.processor)
+            .finish()
+    }
+}
+
+impl<I, O, L: Layer<I, O, P>, P: Processor<I, O>> Processor<I, O> for StackedProcessor<'_, L, P> {
+    fn process(&self, input: I) -> impl Future<Output = O> + Send {
+        self.layer.wrap(self.processor, input)
+    }
+}
+
+impl<I: Send, O, P, Outer, Inner> Layer<I, O, P> for Stack<Outer, Inner>
+where
+    P: Processor<I, O> + Sync,
+    Inner: Layer<I, O, P> + Sync,
+    Outer: for<'a> Layer<I, O, StackedProcessor<'a, Inner, P>> + Sync,
+{
+    async fn wrap(&self, processor: &P, input: I) -> O {
+        let stacked = StackedProcessor {
+            layer: &self.inner,
+            processor,
+        };
+        self.outer.wrap(&stacked, input).await
+    }
+}
+
+/// ## LayerBuilder
+///
+/// Compose many layers and wrap a processor with them at once.
+///
+/// The layer added first is the outermost one, it receives the input first and the output last.
+/// For example,
+/// `LayerBuilder::new().layer(timeout).layer(retry).processor(p)` makes the timeout cover
+/// all the retries, while `LayerBuilder::new().layer(retry).layer(timeout).processor(p)`
+/// applies the timeout to every single attempt.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LayerBuilder<L = Identity> {
+    layer: L,
+}
+
+impl LayerBuilder {
+    /// Create a new layer builder without any layer.
+    pub fn new() -> Self {
+        Self { layer: Identity }
+    }
+}
+
+impl<L> LayerBuilder<L> {
+    /// Add a layer inside all the layers added before.
+    pub fn layer<Inner>(self, layer: Inner) -> LayerBuilder<Stack<L, Inner>> {
+        LayerBuilder {
+            layer: Stack::new(self.layer, layer),
+        }
+    }
+
+    /// Get the composed layer.
+    pub fn into_inner(self) -> L {
+        self.layer
+    }
+
+    /// Wrap a processor with the composed layer.
+    pub fn processor<I, O, P: Processor<I, O>>(
+        self,
+        processor: P,
+    ) -> LayerWrappedProcessor<I, O, P, L>
+    where
+        L: Layer<I, O, P>,
+    {
+        LayerWrappedProcessor::new(self.layer, processor)
+    }
+}
@@ -0,0 +1,154 @@
+use crate::layer::{Layer, ProcessorLayer};
+use crate::processor::Processor;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+use std::time::Duration;
+use tokio::time::error::Elapsed;
+
+/// ## TimeoutLayer
+///
+/// A layer that fails the wrapped processor if it does not finish within a duration.
+///
+/// The wrapped processor must return `Result<O, E>`, the elapsed error is converted into `E`.
+/// For processors that can't fail, use [TimeoutProcessorLayer] instead.
+#[derive(Clone)]
+pub struct TimeoutLayer<F> {
+    duration: Duration,
+    on_elapsed: F,
+}
+
+impl<E: From<Elapsed>> TimeoutLayer<fn(Elapsed) -> E> {
+    /// Create a new timeout layer. The elapsed error is converted with [From].
+    pub fn new(duration: Duration) -> Self {
+        Self {
+            duration,
+            on_elapsed: E::from,
+        }
+    }
+}
+
+impl<F> TimeoutLayer<F> {
+    /// Create a new timeout layer with a function converting the elapsed error.
+    pub fn with_conversion(duration: Duration, on_elapsed: F) -> Self {
+        Self {
+            duration,
+            on_elapsed,
+        }
+    }
+
+    /// Get the duration of the timeout.
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// Get a [ProcessorLayer] with the same duration, wrapping processors with [TimeoutProcessor].
+    pub fn processor_layer(&self) -> TimeoutProcessorLayer {
+        TimeoutProcessorLayer::new(self.duration)
+    }
+}
+
+impl<F> Debug for TimeoutLayer<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TimeoutLayer")
+            .field("duration", &self.duration)
+            .field("on_elapsed", &"Fn(Elapsed) -> E")
+            .finish()
+    }
+}
+
+impl<I: Send, O: Send, E: Send, P: Processor<I, Result<O, E>> + Sync, F: Fn(Elapsed) -> E + Sync>
+    Layer<I, Result<O, E>, P> for TimeoutLayer<F>
+{
+    async fn wrap(&self, processor: &P, input: I) -> Result<O, E> {
+        match tokio::time::timeout(self.duration, processor.process(input)).await {
+            Ok(result) => result,
+            Err(elapsed) => Err((self.on_elapsed)(elapsed)),
+        }
+    }
+}
+
+/// ## TimeoutProcessorLayer
+///
+/// A [ProcessorLayer] that wraps a `Processor<I, O>` with a [TimeoutProcessor], producing a
+/// `Processor<I, Result<O, Elapsed>>`.
+///
+/// Unlike [TimeoutLayer], the wrapped processor doesn't have to return a [Result].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeoutProcessorLayer {
+    duration: Duration,
+}
+
+impl TimeoutProcessorLayer {
+    /// Create a new timeout processor layer.
+    pub fn new(duration: Duration) -> Self {
+        Self { duration }
+    }
+
+    /// Get the duration of the timeout.
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+}
+
+/// ## ForProcessor
+///
+/// The `Marker` of the [ProcessorLayer] implementation of [TimeoutProcessorLayer],
+/// naming the input and output types of the wrapped processor.
+pub struct ForProcessor<I, O> {
+    _phantom: PhantomData<(I, O)>,
+}
+
+impl<I, O, P: Processor<I, O>> ProcessorLayer<P, ForProcessor<I, O>> for TimeoutProcessorLayer {
+    type Wrapped = TimeoutProcessor<I, O, P>;
+
+    fn layer(&self, inner: P) -> Self::Wrapped {
+        TimeoutProcessor::new(inner, self.duration)
+    }
+}
+
+/// ## TimeoutProcessor
+///
+/// A processor that returns [Elapsed] if the wrapped processor does not finish within a duration.
+pub struct TimeoutProcessor<I, O, P: Processor<I, O>> {
+    processor: P,
+    duration: Duration,
+    _phantom: PhantomData<(I, O)>,
+}
+
+impl<I, O, P: Processor<I, O>> TimeoutProcessor<I, O, P> {
+    /// Create a new timeout processor.
+    pub fn new(processor: P, duration: Duration) -> Self {
+        Self {
+            processor,
+            duration,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<I, O, P: Processor<I, O> + Clone> Clone for TimeoutProcessor<I, O, P> {
+    fn clone(&self) -> Self {
+        Self {
+            processor: self.processor.clone(),
+            duration: self.duration,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<I, O, P: Processor<I, O> + Debug> Debug for TimeoutProcessor<I, O, P> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TimeoutProcessor")
+            .field("processor", &self.processor)
+            .field("duration", &self.duration)
+            .finish()
+    }
+}
+
+impl<I: Send + Sync, O: Send + Sync, P: Processor<I, O> + Send + Sync>
+    Processor<I, Result<O, Elapsed>> for TimeoutProcessor<I, O, P>
+{
+    async fn process(&self, input: I) -> Result<O, Elapsed> {
+        tokio::time::timeout(self.duration, self.processor.process(input)).await
+    }
+}
@@ -1,3 +1,7 @@
+#![cfg_attr(
+    all(feature = "try_trait", kanau_nightly),
+    feature(try_trait_v2, try_trait_v2_residual)
+)]
 #![deny(rustdoc::broken_intra_doc_links)]
 #![warn(missing_docs)]
 #![deny(clippy::unwrap_used)]
@@ -14,13 +18,39 @@ pub mod flow;
 /// Something that wraps around a processor.
 pub mod layer;
 
+/// Dispatch inputs to processors by key.
+pub mod router;
+
 #[cfg(feature = "message")]
 /// Message passing tool in MQ.
 pub mod message;
 
+#[cfg(feature = "tower")]
+/// Interoperability with tower services.
+pub mod tower;
+
+#[cfg(feature = "axum")]
+/// Processors as axum handlers.
+pub mod axum;
+
 #[cfg(test)]
 mod tests;
 
+/// Implement [Processor](processor::Processor) or [FinalProcessor](processor::FinalProcessor)
+/// with async functions.
+///
+/// - On an inherent impl block, the methods marked with `#[processor]` implement a processor:
+///   `async fn(&self, input: I) -> O` implements `Processor<I, O>`, and
+///   `async fn(self: Arc<Self>, input: I) -> O` implements `FinalProcessor<I, O>`.
+///   The methods are kept, the trait implementations call them. The other items are left alone.
+/// - On a free `async fn(dep1: &D1, dep2: &D2, input: I) -> O`, a struct named after the
+///   function in PascalCase is generated, holding the dependencies as fields,
+///   and implementing `Processor<I, O>`. The doc comments of the function are copied to the
+///   struct, the other attributes stay on the function.
+///
+/// The future must be `Send`, otherwise the error points at the function.
+pub use kanau_macro::processor;
+
 #[cfg(all(feature = "bincode", feature = "message"))]
 /// Bincode message deserialization.
 pub use kanau_macro::BincodeMessageDe;
@@ -97,4 +97,4 @@ pub trait MessageDe {
     fn from_bytes(bytes: &[u8]) -> Result<Self, Self::DeError>
     where
         Self: Sized;
-}
\ No newline at end of file
+}
@@ -1,5 +1,12 @@
-use futures::stream::FuturesUnordered;
+use crate::layer::ProcessorLayer;
+use futures::StreamExt;
+use futures::stream::{self, FuturesUnordered};
+use std::fmt::Debug;
+use std::marker::PhantomData;
+use std::pin::Pin;
 use std::sync::Arc;
+use thiserror::Error;
+use tokio::task::{JoinError, JoinHandle, JoinSet};
 use tokio_stream::Stream;
 
 /// # Processor
@@ -53,6 +60,259 @@ pub trait RefProcessor<Borrowed, O, Owned = ()> {
         Owned: 'a + 'b;
 }
 
+/// ## processor_fn
+///
+/// Create a [Processor] from a closure or a function returning a future.
+///
+/// Unlike the implementation for `fn(I) -> F`, the closure can capture its environment, and
+/// function items don't need to be coerced to function pointers:
+///
+/// ```ignore
+/// let suffix = "!".to_string();
+/// let processor = processor_fn(move |input: String| {
+///     let suffix = suffix.clone();
+///     async move { input + &suffix }
+/// });
+/// ```
+///
+/// Async closures (`async |input| ...`) work when they don't capture their environment.
+/// A capturing async closure only implements `AsyncFn`, and the future of an `AsyncFn` can't be
+/// required to be `Send` on stable Rust, so write it as a closure returning an async block.
+pub fn processor_fn<F>(f: F) -> FnProcessor<F> {
+    FnProcessor { f }
+}
+
+/// ## FnProcessor
+///
+/// A closure used as a [Processor]. See [processor_fn].
+#[derive(Clone, Copy)]
+pub struct FnProcessor<F> {
+    f: F,
+}
+
+impl<F> Debug for FnProcessor<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FnProcessor")
+            .field("f", &"Fn(I) -> Future")
+            .finish()
+    }
+}
+
+impl<I, O, F: Fn(I) -> Fut, Fut: Future<Output = O> + Send> Processor<I, O> for FnProcessor<F> {
+    fn process(&self, input: I) -> impl Future<Output = O> + Send {
+        (self.f)(input)
+    }
+}
+
+/// ## final_processor_fn
+///
+/// Create a [FinalProcessor] from a closure or a function returning a future.
+pub fn final_processor_fn<F>(f: F) -> FnFinalProcessor<F> {
+    FnFinalProcessor { f }
+}
+
+/// ## FnFinalProcessor
+///
+/// A closure used as a [FinalProcessor]. See [final_processor_fn].
+#[derive(Clone, Copy)]
+pub struct FnFinalProcessor<F> {
+    f: F,
+}
+
+impl<F> Debug for FnFinalProcessor<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FnFinalProcessor")
+            .field("f", &"Fn(I) -> Future")
+            .finish()
+    }
+}
+
+impl<I, O, F: Fn(I) -> Fut, Fut: Future<Output = O> + Send> FinalProcessor<I, O>
+    for FnFinalProcessor<F>
+{
+    fn process(state: Arc<Self>, input: I) -> impl Future<Output = O> + Send {
+        (state.f)(input)
+    }
+}
+
+/// ## ref_processor_fn
+///
+/// Create a [RefProcessor] from a closure or a function taking the borrowed dependencies
+/// and the owned input.
+///
+/// The returned future must not borrow the dependencies, so the closure takes what it needs
+/// from them before returning the future:
+///
+/// ```ignore
+/// let processor = ref_processor_fn(|config: &Config, id: u32| {
+///     let url = config.url.clone();
+///     async move { fetch(url, id).await }
+/// });
+/// ```
+pub fn ref_processor_fn<F>(f: F) -> FnRefProcessor<F> {
+    FnRefProcessor { f }
+}
+
+/// ## FnRefProcessor
+///
+/// A closure used as a [RefProcessor]. See [ref_processor_fn].
+#[derive(Clone, Copy)]
+pub struct FnRefProcessor<F> {
+    f: F,
+}
+
+impl<F> Debug for FnRefProcessor<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FnRefProcessor")
+            .field("f", &"Fn(&Borrowed, Owned) -> Future")
+            .finish()
+    }
+}
+
+impl<Borrowed, O, Owned, F, Fut> RefProcessor<Borrowed, O, Owned> for FnRefProcessor<F>
+where
+    F: Fn(&Borrowed, Owned) -> Fut,
+    Fut: Future<Output = O> + Send + 'static,
+{
+    fn process<'a, 'b>(
+        &'a self,
+        deps: &'b Borrowed,
+        input: Owned,
+    ) -> impl Future<Output = O> + Send + 'a + 'b
+    where
+        Owned: 'a + 'b,
+    {
+        (self.f)(deps, input)
+    }
+}
+
+/// ## FinalAsProcessor
+///
+/// A shared [FinalProcessor] used as a [Processor].
+///
+/// Every call clones the [Arc], so the processor can be passed to the helpers that take a
+/// [Processor], like [parallel_map], or be composed with [ProcessorExt].
+pub struct FinalAsProcessor<P> {
+    processor: Arc<P>,
+}
+
+impl<P> FinalAsProcessor<P> {
+    /// Create a new processor from a final processor.
+    pub fn new(processor: P) -> Self {
+        Self::from_arc(Arc::new(processor))
+    }
+
+    /// Create a new processor from a shared final processor.
+    pub fn from_arc(processor: Arc<P>) -> Self {
+        Self { processor }
+    }
+}
+
+impl<P> Clone for FinalAsProcessor<P> {
+    fn clone(&self) -> Self {
+        Self {
+            processor: self.processor.clone(),
+        }
+    }
+}
+
+impl<P: Debug> Debug for FinalAsProcessor<P> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FinalAsProcessor")
+            .field("processor", &self.processor)
+            .finish()
+    }
+}
+
+impl<I, O, P: FinalProcessor<I, O>> Processor<I, O> for FinalAsProcessor<P> {
+    fn process(&self, input: I) -> impl Future<Output = O> + Send {
+        P::process(self.processor.clone(), input)
+    }
+}
+
+/// ## ProcessorAsFinal
+///
+/// A [Processor] used as a [FinalProcessor].
+///
+/// The returned future owns the [Arc], so it doesn't borrow anything and can be spawned.
+#[derive(Debug, Clone)]
+pub struct ProcessorAsFinal<P> {
+    processor: P,
+}
+
+impl<P> ProcessorAsFinal<P> {
+    /// Create a new final processor from a processor.
+    pub fn new(processor: P) -> Self {
+        Self { processor }
+    }
+
+    /// Get the wrapped processor.
+    pub fn into_inner(self) -> P {
+        self.processor
+    }
+}
+
+impl<I: Send, O, P: Processor<I, O> + Send + Sync> FinalProcessor<I, O> for ProcessorAsFinal<P> {
+    async fn process(state: Arc<Self>, input: I) -> O {
+        state.processor.process(input).await
+    }
+}
+
+/// ## BoundRefProcessor
+///
+/// A [RefProcessor] with its borrowed dependencies bound, used as a [Processor] of the owned
+/// input.
+pub struct BoundRefProcessor<Borrowed, Owned, O, P: RefProcessor<Borrowed, O, Owned>> {
+    processor: P,
+    deps: Borrowed,
+    _phantom: PhantomData<(Owned, O)>,
+}
+
+impl<Borrowed, Owned, O, P: RefProcessor<Borrowed, O, Owned>>
+    BoundRefProcessor<Borrowed, Owned, O, P>
+{
+    /// Bind the dependencies of a ref processor.
+    pub fn new(processor: P, deps: Borrowed) -> Self {
+        Self {
+            processor,
+            deps,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Get the wrapped processor and the dependencies.
+    pub fn into_inner(self) -> (P, Borrowed) {
+        (self.processor, self.deps)
+    }
+}
+
+impl<Borrowed: Clone, Owned, O, P: RefProcessor<Borrowed, O, Owned> + Clone> Clone
+    for BoundRefProcessor<Borrowed, Owned, O, P>
+{
+    fn clone(&self) -> Self {
+        Self::new(self.processor.clone(), self.deps.clone())
+    }
+}
+
+impl<Borrowed: Debug, Owned, O, P: RefProcessor<Borrowed, O, Owned> + Debug> Debug
+    for BoundRefProcessor<Borrowed, Owned, O, P>
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BoundRefProcessor")
+            .field("processor", &self.processor)
+            .field("deps", &self.deps)
+            .finish()
+    }
+}
+
+impl<Borrowed, Owned, O, P: RefProcessor<Borrowed, O, Owned>> Processor<Owned, O>
+    for BoundRefProcessor<Borrowed, Owned, O, P>
+{
+    fn process(&self, input: Owned) -> impl Future<Output = O> + Send {
+        self.processor.process(&self.deps, input)
+    }
+}
+
 /// ## Parallel Map (borrowed version)
 ///
 /// `map` function, but for async functions.
@@ -108,3 +368,1355 @@ where
     let set: FuturesUnordered<_> = iter.map(|input| ref_processor.process(input)).collect();
     set
 }
+
+/// ## Bounded Parallel Map (borrowed version)
+///
+/// Same as [parallel_map_borrowed], but at most `limit` futures are in flight at the same time.
+///
+/// The input items are pulled from the iterator lazily, only when there is room for a new future.
+///
+/// ## Arguments
+///
+/// - `iter` - An iterator that yields references to the input items.
+/// - `ref_processor` - A reference of [RefProcessor] that will be used to process the input items.
+/// - `limit` - The maximum number of futures running concurrently. `0` is treated as `1`.
+///
+/// ## Returns
+///
+/// A stream of output items. The order of the output items is *not guaranteed to be the same* as the input items.
+pub fn parallel_map_borrowed_bounded<'input, I, O, RP, Iter>(
+    iter: Iter,
+    ref_processor: &RP,
+    limit: usize,
+) -> impl Stream<Item = O> + Send
+where
+    I: Send + Sync + 'input,
+    O: Send + Sync,
+    RP: RefProcessor<I, O> + Send + Sync,
+    Iter: Iterator<Item = &'input I> + Send + Sync,
+{
+    stream::iter(iter)
+        .map(|input| ref_processor.process(input, ()))
+        .buffer_unordered(limit.max(1))
+}
+
+/// ## Bounded Parallel Map (owned version)
+///
+/// Same as [parallel_map], but at most `limit` futures are in flight at the same time.
+///
+/// The input items are pulled from the iterator lazily, only when there is room for a new future.
+///
+/// ## Arguments
+///
+/// - `iter` - An iterator that yields the input items.
+/// - `ref_processor` - A reference of [Processor] that will be used to process the input items.
+/// - `limit` - The maximum number of futures running concurrently. `0` is treated as `1`.
+///
+/// ## Returns
+///
+/// A stream of output items. The order of the output items is *not guaranteed to be the same* as the input items.
+pub fn parallel_map_bounded<'p, I, O, P, Iter>(
+    iter: Iter,
+    ref_processor: &'p P,
+    limit: usize,
+) -> impl Stream<Item = O> + Send + 'p
+where
+    I: Send + Sync + 'p,
+    O: Send + Sync + 'p,
+    P: Processor<I, O> + Send + Sync,
+    Iter: Iterator<Item = I> + Send + Sync + 'p,
+{
+    stream::iter(iter)
+        .map(|input| ref_processor.process(input))
+        .buffer_unordered(limit.max(1))
+}
+
+/// ## Ordered Parallel Map (borrowed version)
+///
+/// Same as [parallel_map_borrowed_bounded], but the output items are yielded in the same order
+/// as the input items.
+///
+/// The futures still run concurrently, a finished output is held back until all the outputs
+/// before it are yielded.
+///
+/// ## Arguments
+///
+/// - `iter` - An iterator that yields references to the input items.
+/// - `ref_processor` - A reference of [RefProcessor] that will be used to process the input items.
+/// - `limit` - The maximum number of futures running concurrently. `0` is treated as `1`.
+///
+/// ## Returns
+///
+/// A stream of output items, in the same order as the input items.
+pub fn parallel_map_borrowed_ordered<'input, I, O, RP, Iter>(
+    iter: Iter,
+    ref_processor: &RP,
+    limit: usize,
+) -> impl Stream<Item = O> + Send
+where
+    I: Send + Sync + 'input,
+    O: Send + Sync,
+    RP: RefProcessor<I, O> + Send + Sync,
+    Iter: Iterator<Item = &'input I> + Send + Sync,
+{
+    stream::iter(iter)
+        .map(|input| ref_processor.process(input, ()))
+        .buffered(limit.max(1))
+}
+
+/// ## Ordered Parallel Map (owned version)
+///
+/// Same as [parallel_map_bounded], but the output items are yielded in the same order
+/// as the input items.
+///
+/// The futures still run concurrently, a finished output is held back until all the outputs
+/// before it are yielded.
+///
+/// ## Arguments
+///
+/// - `iter` - An iterator that yields the input items.
+/// - `ref_processor` - A reference of [Processor] that will be used to process the input items.
+/// - `limit` - The maximum number of futures running concurrently. `0` is treated as `1`.
+///
+/// ## Returns
+///
+/// A stream of output items, in the same order as the input items.
+pub fn parallel_map_ordered<'p, I, O, P, Iter>(
+    iter: Iter,
+    ref_processor: &'p P,
+    limit: usize,
+) -> impl Stream<Item = O> + Send + 'p
+where
+    I: Send + Sync + 'p,
+    O: Send + Sync + 'p,
+    P: Processor<I, O> + Send + Sync,
+    Iter: Iterator<Item = I> + Send + Sync + 'p,
+{
+    stream::iter(iter)
+        .map(|input| ref_processor.process(input))
+        .buffered(limit.max(1))
+}
+
+/// ## Enumerated Parallel Map
+///
+/// Same as [parallel_map_bounded], but each output item is paired with the index of its input item.
+///
+/// ## Arguments
+///
+/// - `iter` - An iterator that yields the input items.
+/// - `ref_processor` - A reference of [Processor] that will be used to process the input items.
+/// - `limit` - The maximum number of futures running concurrently. `0` is treated as `1`.
+///
+/// ## Returns
+///
+/// A stream of `(index, output)` pairs. The order of the pairs is *not guaranteed to be the same* as the input items.
+pub fn parallel_map_enumerate<'p, I, O, P, Iter>(
+    iter: Iter,
+    ref_processor: &'p P,
+    limit: usize,
+) -> impl Stream<Item = (usize, O)> + Send + 'p
+where
+    I: Send + Sync + 'p,
+    O: Send + Sync + 'p,
+    P: Processor<I, O> + Send + Sync,
+    Iter: Iterator<Item = I> + Send + Sync + 'p,
+{
+    stream::iter(iter.enumerate())
+        .map(move |(index, input)| async move { (index, ref_processor.process(input).await) })
+        .buffer_unordered(limit.max(1))
+}
+
+/// ## Stream Parallel Map
+///
+/// [parallel_map_bounded], but the input items come from a [Stream] instead of an [Iterator].
+///
+/// At most `limit` futures are in flight at the same time. The input stream is only polled
+/// when there is room for a new future, so a slow processor applies backpressure to the
+/// input stream.
+///
+/// ## Arguments
+///
+/// - `input` - A stream that yields the input items.
+/// - `ref_processor` - A reference of [Processor] that will be used to process the input items.
+/// - `limit` - The maximum number of futures running concurrently. `0` is treated as `1`.
+///
+/// ## Returns
+///
+/// A stream of output items. The order of the output items is *not guaranteed to be the same* as the input items.
+pub fn parallel_map_stream<'p, I, O, P, S>(
+    input: S,
+    ref_processor: &'p P,
+    limit: usize,
+) -> impl Stream<Item = O> + Send + 'p
+where
+    I: Send + Sync + 'p,
+    O: Send + Sync + 'p,
+    P: Processor<I, O> + Send + Sync,
+    S: Stream<Item = I> + Send + 'p,
+{
+    input
+        .map(|input| ref_processor.process(input))
+        .buffer_unordered(limit.max(1))
+}
+
+/// ## Ordered Stream Parallel Map
+///
+/// Same as [parallel_map_stream], but the output items are yielded in the same order
+/// as the input items.
+///
+/// ## Arguments
+///
+/// - `input` - A stream that yields the input items.
+/// - `ref_processor` - A reference of [Processor] that will be used to process the input items.
+/// - `limit` - The maximum number of futures running concurrently. `0` is treated as `1`.
+///
+/// ## Returns
+///
+/// A stream of output items, in the same order as the input items.
+pub fn parallel_map_stream_ordered<'p, I, O, P, S>(
+    input: S,
+    ref_processor: &'p P,
+    limit: usize,
+) -> impl Stream<Item = O> + Send + 'p
+where
+    I: Send + Sync + 'p,
+    O: Send + Sync + 'p,
+    P: Processor<I, O> + Send + Sync,
+    S: Stream<Item = I> + Send + 'p,
+{
+    input
+        .map(|input| ref_processor.process(input))
+        .buffered(limit.max(1))
+}
+
+/// ## Fail-fast Parallel Map
+///
+/// [parallel_map] for processors that return a [Result].
+///
+/// Once any of the processors returns an error, the remaining in-flight futures are dropped
+/// (which cancels them), the remaining input items are not processed, and the error is returned.
+///
+/// ## Arguments
+///
+/// - `iter` - An iterator that yields the input items.
+/// - `ref_processor` - A reference of [Processor] that will be used to process the input items.
+/// - `limit` - The maximum number of futures running concurrently. `0` is treated as `1`.
+///
+/// ## Returns
+///
+/// All the success values, or the first error. The order of the success values is *not guaranteed to be the same* as the input items.
+pub async fn try_parallel_map<I, O, E, P, Iter>(
+    iter: Iter,
+    ref_processor: &P,
+    limit: usize,
+) -> Result<Vec<O>, E>
+where
+    I: Send + Sync,
+    O: Send + Sync,
+    E: Send + Sync,
+    P: Processor<I, Result<O, E>> + Send + Sync,
+    Iter: Iterator<Item = I> + Send + Sync,
+{
+    let mut results = stream::iter(iter)
+        .map(|input| ref_processor.process(input))
+        .buffer_unordered(limit.max(1));
+    let mut outputs = Vec::new();
+    while let Some(result) = results.next().await {
+        outputs.push(result?);
+    }
+    Ok(outputs)
+}
+
+/// ## Partitioned Parallel Map
+///
+/// [parallel_map] for processors that return a [Result].
+///
+/// Unlike [try_parallel_map], every input item is processed even if some of them fail,
+/// so all the errors can be reported at once.
+///
+/// ## Arguments
+///
+/// - `iter` - An iterator that yields the input items.
+/// - `ref_processor` - A reference of [Processor] that will be used to process the input items.
+/// - `limit` - The maximum number of futures running concurrently. `0` is treated as `1`.
+///
+/// ## Returns
+///
+/// The success values and the errors. The order of them is *not guaranteed to be the same* as the input items.
+pub async fn parallel_map_partition<I, O, E, P, Iter>(
+    iter: Iter,
+    ref_processor: &P,
+    limit: usize,
+) -> (Vec<O>, Vec<E>)
+where
+    I: Send + Sync,
+    O: Send + Sync,
+    E: Send + Sync,
+    P: Processor<I, Result<O, E>> + Send + Sync,
+    Iter: Iterator<Item = I> + Send + Sync,
+{
+    let mut results = stream::iter(iter)
+        .map(|input| ref_processor.process(input))
+        .buffer_unordered(limit.max(1));
+    let mut outputs = Vec::new();
+    let mut errors = Vec::new();
+    while let Some(result) = results.next().await {
+        match result {
+            Ok(o) => outputs.push(o),
+            Err(e) => errors.push(e),
+        }
+    }
+    (outputs, errors)
+}
+
+/// Error returned when a spawned processor doesn't complete.
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum SpawnError {
+    /// The processor panicked, with the panic message if it is a string.
+    #[error("Spawned processor panicked: {0}")]
+    Panicked(String),
+    /// The task was aborted, or the runtime was shut down.
+    #[error("Spawned processor was cancelled")]
+    Cancelled,
+}
+
+impl From<JoinError> for SpawnError {
+    fn from(error: JoinError) -> Self {
+        match error.try_into_panic() {
+            Ok(payload) => {
+                let message = match payload.downcast::<String>() {
+                    Ok(message) => *message,
+                    Err(payload) => payload
+                        .downcast_ref::<&str>()
+                        .map_or_else(|| "Box<dyn Any>".to_string(), |m| m.to_string()),
+                };
+                Self::Panicked(message)
+            }
+            Err(_) => Self::Cancelled,
+        }
+    }
+}
+
+/// ## Spawn Process
+///
+/// Spawn a [FinalProcessor] onto a new tokio task.
+///
+/// Await the handle and convert the [JoinError] into a [SpawnError] to handle a panic as an error.
+///
+/// ## Panics
+///
+/// Panics if called outside a tokio runtime.
+pub fn spawn_process<I, O, P>(processor: Arc<P>, input: I) -> JoinHandle<O>
+where
+    I: Send + 'static,
+    O: Send + 'static,
+    P: FinalProcessor<I, O> + Send + Sync + 'static,
+{
+    tokio::spawn(P::process(processor, input))
+}
+
+/// ## Parallel Spawn Map
+///
+/// `map` function, but every input is processed on its own tokio task.
+///
+/// Unlike [parallel_map], which polls all the futures on the caller's task, the tasks run on all
+/// the worker threads of the runtime. A task that panics yields a [SpawnError] instead of
+/// stopping the others. Dropping the stream aborts the tasks that are still running.
+///
+/// Nothing is spawned until the stream is first polled, which spawns the first `limit` inputs.
+/// After that, the next input is pulled from the iterator and spawned each time a task completes.
+///
+/// ## Arguments
+///
+/// - `iter` - An iterator that yields the input items.
+/// - `processor` - The [FinalProcessor] shared by all the tasks.
+/// - `limit` - The maximum number of tasks running concurrently. `0` is treated as `1`.
+///
+/// ## Returns
+///
+/// A stream of output items. The order of the output items is *not guaranteed to be the same* as the input items.
+///
+/// ## Panics
+///
+/// Panics if polled outside a tokio runtime.
+pub fn parallel_spawn_map<I, O, P, Iter>(
+    iter: Iter,
+    processor: Arc<P>,
+    limit: usize,
+) -> impl Stream<Item = Result<O, SpawnError>> + Send
+where
+    I: Send + 'static,
+    O: Send + 'static,
+    P: FinalProcessor<I, O> + Send + Sync + 'static,
+    Iter: Iterator<Item = I> + Send,
+{
+    let limit = limit.max(1);
+    let state = (JoinSet::new(), iter, processor);
+    stream::unfold(state, move |(mut set, mut iter, processor)| async move {
+        while set.len() < limit
+            && let Some(input) = iter.next()
+        {
+            set.spawn(P::process(processor.clone(), input));
+        }
+        let result = set.join_next().await?;
+        // keep the slot busy while the caller handles the output
+        if let Some(input) = iter.next() {
+            set.spawn(P::process(processor.clone(), input));
+        }
+        Some((result.map_err(SpawnError::from), (set, iter, processor)))
+    })
+}
+
+/// ## ProcessorExt
+///
+/// Combinators for [Processor].
+///
+/// This trait is implemented for every [Processor], each combinator returns a named processor
+/// type so the result can be stored in a struct, cloned or wrapped again.
+pub trait ProcessorExt<I, O>: Processor<I, O> {
+    /// Map the output with a function.
+    fn map<O2, F: Fn(O) -> O2>(self, f: F) -> MapProcessor<I, O, O2, Self, F>
+    where
+        Self: Sized,
+    {
+        MapProcessor::new(self, f)
+    }
+
+    /// Map the output with an async function.
+    fn map_async<O2, Fut: Future<Output = O2>, F: Fn(O) -> Fut>(
+        self,
+        f: F,
+    ) -> MapAsyncProcessor<I, O, O2, Self, F>
+    where
+        Self: Sized,
+    {
+        MapAsyncProcessor::new(self, f)
+    }
+
+    /// Pass the output to another processor.
+    fn and_then<O2, P2: Processor<O, O2>>(self, next: P2) -> AndThenProcessor<I, O, O2, Self, P2>
+    where
+        Self: Sized,
+    {
+        AndThenProcessor::new(self, next)
+    }
+
+    /// Convert the input with a function before passing it to the processor.
+    fn map_input<I0, F: Fn(I0) -> I>(self, f: F) -> MapInputProcessor<I0, I, O, Self, F>
+    where
+        Self: Sized,
+    {
+        MapInputProcessor::new(self, f)
+    }
+
+    /// Call a function with a reference of the output, then return the output as is.
+    fn inspect<F: Fn(&O)>(self, f: F) -> InspectProcessor<I, O, Self, F>
+    where
+        Self: Sized,
+    {
+        InspectProcessor::new(self, f)
+    }
+
+    /// Wrap the processor with a [ProcessorLayer], which includes every [Layer](crate::layer::Layer).
+    fn with_layer<Marker, L: ProcessorLayer<Self, Marker>>(self, layer: &L) -> L::Wrapped
+    where
+        Self: Sized,
+    {
+        layer.layer(self)
+    }
+
+    /// Erase the type of the processor.
+    fn boxed(self) -> BoxProcessor<I, O>
+    where
+        Self: Sized + Send + Sync + 'static,
+    {
+        BoxProcessor::new(self)
+    }
+
+    /// Erase the type of the processor, and share it so it can be cloned.
+    fn shared(self) -> ArcProcessor<I, O>
+    where
+        Self: Sized + Send + Sync + 'static,
+    {
+        ArcProcessor::new(self)
+    }
+}
+
+impl<I, O, P: Processor<I, O>> ProcessorExt<I, O> for P {}
+
+/// ## MapProcessor
+///
+/// A processor whose output is mapped with a function. See [ProcessorExt::map].
+pub struct MapProcessor<I, O1, O2, P: Processor<I, O1>, F: Fn(O1) -> O2> {
+    processor: P,
+    f: F,
+    _phantom: PhantomData<(I, O1, O2)>,
+}
+
+impl<I, O1, O2, P: Processor<I, O1>, F: Fn(O1) -> O2> MapProcessor<I, O1, O2, P, F> {
+    /// Create a new map processor.
+    pub fn new(processor: P, f: F) -> Self {
+        Self {
+            processor,
+            f,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<I, O1, O2, P: Processor<I, O1> + Clone, F: Fn(O1) -> O2 + Clone> Clone
+    for MapProcessor<I, O1, O2, P, F>
+{
+    fn clone(&self) -> Self {
+        Self {
+            processor: self.processor.clone(),
+            f: self.f.clone(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<I, O1, O2, P: Processor<I, O1> + Debug, F: Fn(O1) -> O2> Debug
+    for MapProcessor<I, O1, O2, P, F>
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MapProcessor")
+            .field("processor", &self.processor)
+            .field("f", &"Fn(O1) -> O2")
+            .finish()
+    }
+}
+
+impl<
+    I: Send + Sync,
+    O1: Send + Sync,
+    O2: Send + Sync,
+    P: Processor<I, O1> + Send + Sync,
+    F: Fn(O1) -> O2 + Send + Sync,
+> Processor<I, O2> for MapProcessor<I, O1, O2, P, F>
+{
+    async fn process(&self, input: I) -> O2 {
+        (self.f)(self.processor.process(input).await)
+    }
+}
+
+/// ## MapAsyncProcessor
+///
+/// A processor whose output is mapped with an async function. See [ProcessorExt::map_async].
+pub struct MapAsyncProcessor<I, O1, O2, P: Processor<I, O1>, F> {
+    processor: P,
+    f: F,
+    _phantom: PhantomData<(I, O1, O2)>,
+}
+
+impl<I, O1, O2, P: Processor<I, O1>, F> MapAsyncProcessor<I, O1, O2, P, F> {
+    /// Create a new async map processor.
+    pub fn new(processor: P, f: F) -> Self {
+        Self {
+            processor,
+            f,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<I, O1, O2, P: Processor<I, O1> + Clone, F: Clone> Clone
+    for MapAsyncProcessor<I, O1, O2, P, F>
+{
+    fn clone(&self) -> Self {
+        Self {
+            processor: self.processor.clone(),
+            f: self.f.clone(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<I, O1, O2, P: Processor<I, O1> + Debug, F> Debug for MapAsyncProcessor<I, O1, O2, P, F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MapAsyncProcessor")
+            .field("processor", &self.processor)
+            .field("f", &"Fn(O1) -> impl Future<Output = O2>")
+            .finish()
+    }
+}
+
+impl<
+    I: Send + Sync,
+    O1: Send + Sync,
+    O2: Send + Sync,
+    P: Processor<I, O1> + Send + Sync,
+    Fut: Future<Output = O2> + Send,
+    F: Fn(O1) -> Fut + Send + Sync,
+> Processor<I, O2> for MapAsyncProcessor<I, O1, O2, P, F>
+{
+    async fn process(&self, input: I) -> O2 {
+        (self.f)(self.processor.process(input).await).await
+    }
+}
+
+/// ## AndThenProcessor
+///
+/// Two processors composed sequentially, the output of the first one is the input of the
+/// second one. See [ProcessorExt::and_then].
+pub struct AndThenProcessor<I, O1, O2, P1: Processor<I, O1>, P2: Processor<O1, O2>> {
+    first: P1,
+    second: P2,
+    _phantom: PhantomData<(I, O1, O2)>,
+}
+
+impl<I, O1, O2, P1: Processor<I, O1>, P2: Processor<O1, O2>> AndThenProcessor<I, O1, O2, P1, P2> {
+    /// Create a new and-then processor.
+    pub fn new(first: P1, second: P2) -> Self {
+        Self {
+            first,
+            second,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<I, O1, O2, P1: Processor<I, O1> + Clone, P2: Processor<O1, O2> + Clone> Clone
+    for AndThenProcessor<I, O1, O2, P1, P2>
+{
+    fn clone(&self) -> Self {
+        Self {
+            first: self.first.clone(),
+            second: self.second.clone(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<I, O1, O2, P1: Processor<I, O1> + Debug, P2: Processor<O1, O2> + Debug> Debug
+    for AndThenProcessor<I, O1, O2, P1, P2>
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AndThenProcessor")
+            .field("first", &self.first)
+            .field("second", &self.second)
+            .finish()
+    }
+}
+
+impl<
+    I: Send + Sync,
+    O1: Send + Sync,
+    O2: Send + Sync,
+    P1: Processor<I, O1> + Send + Sync,
+    P2: Processor<O1, O2> + Send + Sync,
+> Processor<I, O2> for AndThenProcessor<I, O1, O2, P1, P2>
+{
+    async fn process(&self, input: I) -> O2 {
+        let intermediate = self.first.process(input).await;
+        self.second.process(intermediate).await
+    }
+}
+
+/// ## MapInputProcessor
+///
+/// A processor whose input is converted with a function first. See [ProcessorExt::map_input].
+pub struct MapInputProcessor<I1, I2, O, P: Processor<I2, O>, F: Fn(I1) -> I2> {
+    processor: P,
+    f: F,
+    _phantom: PhantomData<(I1, I2, O)>,
+}
+
+impl<I1, I2, O, P: Processor<I2, O>, F: Fn(I1) -> I2> MapInputProcessor<I1, I2, O, P, F> {
+    /// Create a new input-mapping processor.
+    pub fn new(processor: P, f: F) -> Self {
+        Self {
+            processor,
+            f,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<I1, I2, O, P: Processor<I2, O> + Clone, F: Fn(I1) -> I2 + Clone> Clone
+    for MapInputProcessor<I1, I2, O, P, F>
+{
+    fn clone(&self) -> Self {
+        Self {
+            processor: self.processor.clone(),
+            f: self.f.clone(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<I1, I2, O, P: Processor<I2, O> + Debug, F: Fn(I1) -> I2> Debug
+    for MapInputProcessor<I1, I2, O, P, F>
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MapInputProcessor")
+            .field("processor", &self.processor)
+            .field("f", &"Fn(I1) -> I2")
+            .finish()
+    }
+}
+
+impl<
+    I1: Send + Sync,
+    I2: Send + Sync,
+    O: Send + Sync,
+    P: Processor<I2, O> + Send + Sync,
+    F: Fn(I1) -> I2 + Send + Sync,
+> Processor<I1, O> for MapInputProcessor<I1, I2, O, P, F>
+{
+    async fn process(&self, input: I1) -> O {
+        self.processor.process((self.f)(input)).await
+    }
+}
+
+/// ## InspectProcessor
+///
+/// A processor that passes a reference of its output to a function. See [ProcessorExt::inspect].
+pub struct InspectProcessor<I, O, P: Processor<I, O>, F: Fn(&O)> {
+    processor: P,
+    f: F,
+    _phantom: PhantomData<(I, O)>,
+}
+
+impl<I, O, P: Processor<I, O>, F: Fn(&O)> InspectProcessor<I, O, P, F> {
+    /// Create a new inspect processor.
+    pub fn new(processor: P, f: F) -> Self {
+        Self {
+            processor,
+            f,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<I, O, P: Processor<I, O> + Clone, F: Fn(&O) + Clone> Clone for InspectProcessor<I, O, P, F> {
+    fn clone(&self) -> Self {
+        Self {
+            processor: self.processor.clone(),
+            f: self.f.clone(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<I, O, P: Processor<I, O> + Debug, F: Fn(&O)> Debug for InspectProcessor<I, O, P, F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("InspectProcessor")
+            .field("processor", &self.processor)
+            .field("f", &"Fn(&O)")
+            .finish()
+    }
+}
+
+impl<I: Send + Sync, O: Send + Sync, P: Processor<I, O> + Send + Sync, F: Fn(&O) + Send + Sync>
+    Processor<I, O> for InspectProcessor<I, O, P, F>
+{
+    async fn process(&self, input: I) -> O {
+        let output = self.processor.process(input).await;
+        (self.f)(&output);
+        output
+    }
+}
+
+/// A boxed future returned by the type-erased processors.
+pub type BoxFuture<'a, O> = Pin<Box<dyn Future<Output = O> + Send + 'a>>;
+
+// object-safe version of Processor, used by BoxProcessor and ArcProcessor
+trait DynProcessor<I, O>: Send + Sync {
+    fn process_boxed<'a>(&'a self, input: I) -> BoxFuture<'a, O>
+    where
+        I: 'a,
+        O: 'a;
+}
+
+impl<I, O, P: Processor<I, O> + Send + Sync> DynProcessor<I, O> for P {
+    fn process_boxed<'a>(&'a self, input: I) -> BoxFuture<'a, O>
+    where
+        I: 'a,
+        O: 'a,
+    {
+        Box::pin(self.process(input))
+    }
+}
+
+/// ## BoxProcessor
+///
+/// A type-erased processor. See [ProcessorExt::boxed].
+///
+/// Processors of different types with the same input and output can be stored together,
+/// for example in a `HashMap<String, BoxProcessor<I, O>>`.
+pub struct BoxProcessor<I, O> {
+    inner: Box<dyn DynProcessor<I, O>>,
+}
+
+impl<I, O> BoxProcessor<I, O> {
+    /// Erase the type of a processor.
+    pub fn new<P: Processor<I, O> + Send + Sync + 'static>(processor: P) -> Self {
+        Self {
+            inner: Box::new(processor),
+        }
+    }
+
+    /// Process the input, returning a boxed future.
+    pub fn process_boxed<'a>(&'a self, input: I) -> BoxFuture<'a, O>
+    where
+        I: 'a,
+        O: 'a,
+    {
+        self.inner.process_boxed(input)
+    }
+
+    /// Wrap the processor with a layer, and erase the type again.
+    pub fn with_layer<Marker, L: ProcessorLayer<Self, Marker>>(self, layer: &L) -> Self
+    where
+        L::Wrapped: Processor<I, O> + Send + Sync + 'static,
+    {
+        Self::new(layer.layer(self))
+    }
+
+    /// Share the processor, so it can be cloned.
+    pub fn into_shared(self) -> ArcProcessor<I, O> {
+        ArcProcessor {
+            inner: Arc::from(self.inner),
+        }
+    }
+}
+
+impl<I, O> Debug for BoxProcessor<I, O> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BoxProcessor").finish_non_exhaustive()
+    }
+}
+
+impl<I: Send, O: Send> Processor<I, O> for BoxProcessor<I, O> {
+    fn process(&self, input: I) -> impl Future<Output = O> + Send {
+        self.inner.process_boxed(input)
+    }
+}
+
+/// ## ArcProcessor
+///
+/// A type-erased processor shared behind an [Arc], so it is cheap to clone.
+/// See [ProcessorExt::shared].
+pub struct ArcProcessor<I, O> {
+    inner: Arc<dyn DynProcessor<I, O>>,
+}
+
+impl<I, O> ArcProcessor<I, O> {
+    /// Erase the type of a processor.
+    pub fn new<P: Processor<I, O> + Send + Sync + 'static>(processor: P) -> Self {
+        Self {
+            inner: Arc::new(processor),
+        }
+    }
+
+    /// Process the input, returning a boxed future.
+    pub fn process_boxed<'a>(&'a self, input: I) -> BoxFuture<'a, O>
+    where
+        I: 'a,
+        O: 'a,
+    {
+        self.inner.process_boxed(input)
+    }
+
+    /// Wrap the processor with a layer, and erase the type again.
+    pub fn with_layer<Marker, L: ProcessorLayer<Self, Marker>>(self, layer: &L) -> Self
+    where
+        L::Wrapped: Processor<I, O> + Send + Sync + 'static,
+    {
+        Self::new(layer.layer(self))
+    }
+}
+
+impl<I, O> Clone for ArcProcessor<I, O> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<I, O> Debug for ArcProcessor<I, O> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ArcProcessor").finish_non_exhaustive()
+    }
+}
+
+impl<I, O> From<BoxProcessor<I, O>> for ArcProcessor<I, O> {
+    fn from(processor: BoxProcessor<I, O>) -> Self {
+        processor.into_shared()
+    }
+}
+
+impl<I: Send, O: Send> Processor<I, O> for ArcProcessor<I, O> {
+    fn process(&self, input: I) -> impl Future<Output = O> + Send {
+        self.inner.process_boxed(input)
+    }
+}
+
+/// ## TryProcessor
+///
+/// Combinators for processors that return a [Result].
+///
+/// This trait is implemented for every `Processor<I, Result<O, E>>`. These combinators work like
+/// [EarlyReturn::try_map](crate::flow::EarlyReturn::try_map) and
+/// [EarlyReturn::try_process_map](crate::flow::EarlyReturn::try_process_map), but on the
+/// processor level.
+pub trait TryProcessor<I, O, E>: Processor<I, Result<O, E>> {
+    /// Map the success value with a function.
+    fn map_ok<O2, F: Fn(O) -> O2>(self, f: F) -> MapOkProcessor<I, O, O2, E, Self, F>
+    where
+        Self: Sized,
+    {
+        MapOkProcessor::new(self, f)
+    }
+
+    /// Map the error value with a function.
+    fn map_err<E2, F: Fn(E) -> E2>(self, f: F) -> MapErrProcessor<I, O, E, E2, Self, F>
+    where
+        Self: Sized,
+    {
+        MapErrProcessor::new(self, f)
+    }
+
+    /// Pass the success value to a fallible processor. Return the error if any of them fails.
+    fn and_then_ok<O2, E2: Into<E>, P2: Processor<O, Result<O2, E2>>>(
+        self,
+        next: P2,
+    ) -> AndThenOkProcessor<I, O, O2, E, E2, Self, P2>
+    where
+        Self: Sized,
+    {
+        AndThenOkProcessor::new(self, next)
+    }
+
+    /// Pass the error value to a fallible processor, which may recover from the error.
+    fn or_else<E2, P2: Processor<E, Result<O, E2>>>(
+        self,
+        fallback: P2,
+    ) -> OrElseProcessor<I, O, E, E2, Self, P2>
+    where
+        Self: Sized,
+    {
+        OrElseProcessor::new(self, fallback)
+    }
+
+    /// Convert the error value with [Into].
+    fn err_into<E2>(self) -> ErrIntoProcessor<I, O, E, E2, Self>
+    where
+        Self: Sized,
+        E: Into<E2>,
+    {
+        ErrIntoProcessor::new(self)
+    }
+
+    /// Compute a success value from the error value, so the processor never fails.
+    fn unwrap_or_else<F: Fn(E) -> O>(self, f: F) -> UnwrapOrElseProcessor<I, O, E, Self, F>
+    where
+        Self: Sized,
+    {
+        UnwrapOrElseProcessor::new(self, f)
+    }
+}
+
+impl<I, O, E, P: Processor<I, Result<O, E>>> TryProcessor<I, O, E> for P {}
+
+/// ## MapOkProcessor
+///
+/// A fallible processor whose success value is mapped with a function. See [TryProcessor::map_ok].
+pub struct MapOkProcessor<I, O1, O2, E, P: Processor<I, Result<O1, E>>, F: Fn(O1) -> O2> {
+    processor: P,
+    f: F,
+    _phantom: PhantomData<(I, O1, O2, E)>,
+}
+
+impl<I, O1, O2, E, P: Processor<I, Result<O1, E>>, F: Fn(O1) -> O2>
+    MapOkProcessor<I, O1, O2, E, P, F>
+{
+    /// Create a new map-ok processor.
+    pub fn new(processor: P, f: F) -> Self {
+        Self {
+            processor,
+            f,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<I, O1, O2, E, P: Processor<I, Result<O1, E>> + Clone, F: Fn(O1) -> O2 + Clone> Clone
+    for MapOkProcessor<I, O1, O2, E, P, F>
+{
+    fn clone(&self) -> Self {
+        Self {
+            processor: self.processor.clone(),
+            f: self.f.clone(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<I, O1, O2, E, P: Processor<I, Result<O1, E>> + Debug, F: Fn(O1) -> O2> Debug
+    for MapOkProcessor<I, O1, O2, E, P, F>
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MapOkProcessor")
+            .field("processor", &self.processor)
+            .field("f", &"Fn(O1) -> O2")
+            .finish()
+    }
+}
+
+impl<
+    I: Send + Sync,
+    O1: Send + Sync,
+    O2: Send + Sync,
+    E: Send + Sync,
+    P: Processor<I, Result<O1, E>> + Send + Sync,
+    F: Fn(O1) -> O2 + Send + Sync,
+> Processor<I, Result<O2, E>> for MapOkProcessor<I, O1, O2, E, P, F>
+{
+    async fn process(&self, input: I) -> Result<O2, E> {
+        self.processor.process(input).await.map(&self.f)
+    }
+}
+
+/// ## MapErrProcessor
+///
+/// A fallible processor whose error value is mapped with a function. See [TryProcessor::map_err].
+pub struct MapErrProcessor<I, O, E1, E2, P: Processor<I, Result<O, E1>>, F: Fn(E1) -> E2> {
+    processor: P,
+    f: F,
+    _phantom: PhantomData<(I, O, E1, E2)>,
+}
+
+impl<I, O, E1, E2, P: Processor<I, Result<O, E1>>, F: Fn(E1) -> E2>
+    MapErrProcessor<I, O, E1, E2, P, F>
+{
+    /// Create a new map-err processor.
+    pub fn new(processor: P, f: F) -> Self {
+        Self {
+            processor,
+            f,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<I, O, E1, E2, P: Processor<I, Result<O, E1>> + Clone, F: Fn(E1) -> E2 + Clone> Clone
+    for MapErrProcessor<I, O, E1, E2, P, F>
+{
+    fn clone(&self) -> Self {
+        Self {
+            processor: self.processor.clone(),
+            f: self.f.clone(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<I, O, E1, E2, P: Processor<I, Result<O, E1>> + Debug, F: Fn(E1) -> E2> Debug
+    for MapErrProcessor<I, O, E1, E2, P, F>
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MapErrProcessor")
+            .field("processor", &self.processor)
+            .field("f", &"Fn(E1) -> E2")
+            .finish()
+    }
+}
+
+impl<
+    I: Send + Sync,
+    O: Send + Sync,
+    E1: Send + Sync,
+    E2: Send + Sync,
+    P: Processor<I, Result<O, E1>> + Send + Sync,
+    F: Fn(E1) -> E2 + Send + Sync,
+> Processor<I, Result<O, E2>> for MapErrProcessor<I, O, E1, E2, P, F>
+{
+    async fn process(&self, input: I) -> Result<O, E2> {
+        self.processor.process(input).await.map_err(&self.f)
+    }
+}
+
+/// ## AndThenOkProcessor
+///
+/// Two fallible processors composed sequentially, the second one is only called when the first
+/// one succeeds. See [TryProcessor::and_then_ok].
+pub struct AndThenOkProcessor<
+    I,
+    O1,
+    O2,
+    E1,
+    E2: Into<E1>,
+    P1: Processor<I, Result<O1, E1>>,
+    P2: Processor<O1, Result<O2, E2>>,
+> {
+    first: P1,
+    second: P2,
+    _phantom: PhantomData<(I, O1, O2, E1, E2)>,
+}
+
+impl<
+    I,
+    O1,
+    O2,
+    E1,
+    E2: Into<E1>,
+    P1: Processor<I, Result<O1, E1>>,
+    P2: Processor<O1, Result<O2, E2>>,
+> AndThenOkProcessor<I, O1, O2, E1, E2, P1, P2>
+{
+    /// Create a new and-then-ok processor.
+    pub fn new(first: P1, second: P2) -> Self {
+        Self {
+            first,
+            second,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<
+    I,
+    O1,
+    O2,
+    E1,
+    E2: Into<E1>,
+    P1: Processor<I, Result<O1, E1>> + Clone,
+    P2: Processor<O1, Result<O2, E2>> + Clone,
+> Clone for AndThenOkProcessor<I, O1, O2, E1, E2, P1, P2>
+{
+    fn clone(&self) -> Self {
+        Self {
+            first: self.first.clone(),
+            second: self.second.clone(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<
+    I,
+    O1,
+    O2,
+    E1,
+    E2: Into<E1>,
+    P1: Processor<I, Result<O1, E1>> + Debug,
+    P2: Processor<O1, Result<O2, E2>> + Debug,
+> Debug for AndThenOkProcessor<I, O1, O2, E1, E2, P1, P2>
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AndThenOkProcessor")
+            .field("first", &self.first)
+            .field("second", &self.second)
+            .finish()
+    }
+}
+
+impl<
+    I: Send + Sync,
+    O1: Send + Sync,
+    O2: Send + Sync,
+    E1: Send + Sync,
+    E2: Into<E1> + Send + Sync,
+    P1: Processor<I, Result<O1, E1>> + Send + Sync,
+    P2: Processor<O1, Result<O2, E2>> + Send + Sync,
+> Processor<I, Result<O2, E1>> for AndThenOkProcessor<I, O1, O2, E1, E2, P1, P2>
+{
+    async fn process(&self, input: I) -> Result<O2, E1> {
+        let intermediate = self.first.process(input).await?;
+        self.second.process(intermediate).await.map_err(Into::into)
+    }
+}
+
+/// ## OrElseProcessor
+///
+/// A fallible processor with a fallback processor that is called with the error value.
+/// See [TryProcessor::or_else].
+pub struct OrElseProcessor<
+    I,
+    O,
+    E1,
+    E2,
+    P1: Processor<I, Result<O, E1>>,
+    P2: Processor<E1, Result<O, E2>>,
+> {
+    processor: P1,
+    fallback: P2,
+    _phantom: PhantomData<(I, O, E1, E2)>,
+}
+
+impl<I, O, E1, E2, P1: Processor<I, Result<O, E1>>, P2: Processor<E1, Result<O, E2>>>
+    OrElseProcessor<I, O, E1, E2, P1, P2>
+{
+    /// Create a new or-else processor.
+    pub fn new(processor: P1, fallback: P2) -> Self {
+        Self {
+            processor,
+            fallback,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<
+    I,
+    O,
+    E1,
+    E2,
+    P1: Processor<I, Result<O, E1>> + Clone,
+    P2: Processor<E1, Result<O, E2>> + Clone,
+> Clone for OrElseProcessor<I, O, E1, E2, P1, P2>
+{
+    fn clone(&self) -> Self {
+        Self {
+            processor: self.processor.clone(),
+            fallback: self.fallback.clone(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<
+    I,
+    O,
+    E1,
+    E2,
+    P1: Processor<I, Result<O, E1>> + Debug,
+    P2: Processor<E1, Result<O, E2>> + Debug,
+> Debug for OrElseProcessor<I, O, E1, E2, P1, P2>
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OrElseProcessor")
+            .field("processor", &self.processor)
+            .field("fallback", &self.fallback)
+            .finish()
+    }
+}
+
+impl<
+    I: Send + Sync,
+    O: Send + Sync,
+    E1: Send + Sync,
+    E2: Send + Sync,
+    P1: Processor<I, Result<O, E1>> + Send + Sync,
+    P2: Processor<E1, Result<O, E2>> + Send + Sync,
+> Processor<I, Result<O, E2>> for OrElseProcessor<I, O, E1, E2, P1, P2>
+{
+    async fn process(&self, input: I) -> Result<O, E2> {
+        match self.processor.process(input).await {
+            Ok(o) => Ok(o),
+            Err(e) => self.fallback.process(e).await,
+        }
+    }
+}
+
+/// ## ErrIntoProcessor
+///
+/// A fallible processor whose error value is converted with [Into]. See [TryProcessor::err_into].
+pub struct ErrIntoProcessor<I, O, E1: Into<E2>, E2, P: Processor<I, Result<O, E1>>> {
+    processor: P,
+    _phantom: PhantomData<(I, O, E1, E2)>,
+}
+
+impl<I, O, E1: Into<E2>, E2, P: Processor<I, Result<O, E1>>> ErrIntoProcessor<I, O, E1, E2, P> {
+    /// Create a new err-into processor.
+    pub fn new(processor: P) -> Self {
+        Self {
+            processor,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<I, O, E1: Into<E2>, E2, P: Processor<I, Result<O, E1>> + Clone> Clone
+    for ErrIntoProcessor<I, O, E1, E2, P>
+{
+    fn clone(&self) -> Self {
+        Self {
+            processor: self.processor.clone(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<I, O, E1: Into<E2>, E2, P: Processor<I, Result<O, E1>> + Debug> Debug
+    for ErrIntoProcessor<I, O, E1, E2, P>
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ErrIntoProcessor")
+            .field("processor", &self.processor)
+            .finish()
+    }
+}
+
+impl<
+    I: Send + Sync,
+    O: Send + Sync,
+    E1: Into<E2> + Send + Sync,
+    E2: Send + Sync,
+    P: Processor<I, Result<O, E1>> + Send + Sync,
+> Processor<I, Result<O, E2>> for ErrIntoProcessor<I, O, E1, E2, P>
+{
+    async fn process(&self, input: I) -> Result<O, E2> {
+        self.processor.process(input).await.map_err(Into::into)
+    }
+}
+
+/// ## UnwrapOrElseProcessor
+///
+/// A fallible processor that computes a success value from the error value.
+/// See [TryProcessor::unwrap_or_else].
+pub struct UnwrapOrElseProcessor<I, O, E, P: Processor<I, Result<O, E>>, F: Fn(E) -> O> {
+    processor: P,
+    f: F,
+    _phantom: PhantomData<(I, O, E)>,
+}
+
+impl<I, O, E, P: Processor<I, Result<O, E>>, F: Fn(E) -> O> UnwrapOrElseProcessor<I, O, E, P, F> {
+    /// Create a new unwrap-or-else processor.
+    pub fn new(processor: P, f: F) -> Self {
+        Self {
+            processor,
+            f,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<I, O, E, P: Processor<I, Result<O, E>> + Clone, F: Fn(E) -> O + Clone> Clone
+    for UnwrapOrElseProcessor<I, O, E, P, F>
+{
+    fn clone(&self) -> Self {
+        Self {
+            processor: self.processor.clone(),
+            f: self.f.clone(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<I, O, E, P: Processor<I, Result<O, E>> + Debug, F: Fn(E) -> O> Debug
+    for UnwrapOrElseProcessor<I, O, E, P, F>
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UnwrapOrElseProcessor")
+            .field("processor", &self.processor)
+            .field("f", &"Fn(E) -> O")
+            .finish()
+    }
+}
+
+impl<
+    I: Send + Sync,
+    O: Send + Sync,
+    E: Send + Sync,
+    P: Processor<I, Result<O, E>> + Send + Sync,
+    F: Fn(E) -> O + Send + Sync,
+> Processor<I, O> for UnwrapOrElseProcessor<I, O, E, P, F>
+{
+    async fn process(&self, input: I) -> O {
+        self.processor.process(input).await.unwrap_or_else(&self.f)
+    }
+}
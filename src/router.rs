@@ -0,0 +1,194 @@
+use crate::layer::ProcessorLayer;
+use crate::processor::{ArcProcessor, BoxFuture, Processor};
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::sync::Arc;
+use thiserror::Error;
+
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq)]
+#[error("No route for key {key:?}")]
+/// Error returned by a [Router] without a fallback processor when no route matches the key.
+pub struct NoRouteError<K> {
+    /// The key extracted from the input.
+    pub key: K,
+}
+
+type KeyExtractor<K, I> = Arc<dyn Fn(&I) -> K + Send + Sync>;
+
+enum Fallback<K, I, O> {
+    Processor(ArcProcessor<I, O>),
+    Error(Arc<dyn Fn(K) -> O + Send + Sync>),
+}
+
+impl<K, I, O> Clone for Fallback<K, I, O> {
+    fn clone(&self) -> Self {
+        match self {
+            Fallback::Processor(processor) => Fallback::Processor(processor.clone()),
+            Fallback::Error(f) => Fallback::Error(f.clone()),
+        }
+    }
+}
+
+/// ## Router
+///
+/// A processor that dispatches the input to one of many processors.
+///
+/// A key is extracted from every input, and the input is processed by the route registered for
+/// that key. When no route matches, the input goes to the fallback processor, or a
+/// [NoRouteError] is returned.
+///
+/// To wrap every call with a layer, including the routes registered later and the no-route error,
+/// wrap the whole router with [ProcessorExt::with_layer](crate::processor::ProcessorExt::with_layer).
+/// [Router::layer_route] wraps a single route.
+///
+/// ```ignore
+/// let router = Router::new(|message: &Message| message.kind.clone())
+///     .route("created".to_string(), OnCreated)
+///     .route("deleted".to_string(), OnDeleted)
+///     .layer_route(&"deleted".to_string(), &RetryLayer::new(policy))
+///     .with_layer(&TimeoutLayer::new(Duration::from_secs(5)));
+/// ```
+pub struct Router<K, I, O> {
+    extractor: KeyExtractor<K, I>,
+    routes: HashMap<K, ArcProcessor<I, O>>,
+    fallback: Fallback<K, I, O>,
+}
+
+impl<K, I, T, E: From<NoRouteError<K>>> Router<K, I, Result<T, E>> {
+    /// Create a new router without any route, returning a [NoRouteError] when no route
+    /// matches.
+    pub fn new<F: Fn(&I) -> K + Send + Sync + 'static>(extractor: F) -> Self {
+        Self {
+            extractor: Arc::new(extractor),
+            routes: HashMap::new(),
+            fallback: Fallback::Error(Arc::new(|key| Err(NoRouteError { key }.into()))),
+        }
+    }
+}
+
+impl<K, I, O> Router<K, I, O> {
+    /// Create a new router without any route, processing the input with `fallback` when no
+    /// route matches.
+    pub fn with_fallback<F, P>(extractor: F, fallback: P) -> Self
+    where
+        F: Fn(&I) -> K + Send + Sync + 'static,
+        P: Processor<I, O> + Send + Sync + 'static,
+    {
+        Self {
+            extractor: Arc::new(extractor),
+            routes: HashMap::new(),
+            fallback: Fallback::Processor(ArcProcessor::new(fallback)),
+        }
+    }
+
+    /// Replace the fallback processor.
+    pub fn fallback<P: Processor<I, O> + Send + Sync + 'static>(self, fallback: P) -> Self {
+        Self {
+            fallback: Fallback::Processor(ArcProcessor::new(fallback)),
+            ..self
+        }
+    }
+
+    /// Get the number of routes.
+    pub fn len(&self) -> usize {
+        self.routes.len()
+    }
+
+    /// Check whether there is no route.
+    pub fn is_empty(&self) -> bool {
+        self.routes.is_empty()
+    }
+}
+
+impl<K: Eq + Hash, I, O> Router<K, I, O> {
+    /// Register a processor for a key, replacing the previous one.
+    pub fn route<P: Processor<I, O> + Send + Sync + 'static>(
+        mut self,
+        key: K,
+        processor: P,
+    ) -> Self {
+        self.routes.insert(key, ArcProcessor::new(processor));
+        self
+    }
+
+    /// Wrap the routes registered so far, and the current fallback processor, with a layer.
+    ///
+    /// The routes registered after, a fallback processor set after, and the no-route error are
+    /// not wrapped. Use [ProcessorExt::with_layer](crate::processor::ProcessorExt::with_layer)
+    /// on the router to wrap every call.
+    pub fn layer_existing_routes<Marker, L>(self, layer: &L) -> Self
+    where
+        L: ProcessorLayer<ArcProcessor<I, O>, Marker>,
+        L::Wrapped: Processor<I, O> + Send + Sync + 'static,
+    {
+        let routes = self
+            .routes
+            .into_iter()
+            .map(|(key, processor)| (key, processor.with_layer(layer)))
+            .collect();
+        let fallback = match self.fallback {
+            Fallback::Processor(processor) => Fallback::Processor(processor.with_layer(layer)),
+            Fallback::Error(f) => Fallback::Error(f),
+        };
+        Self {
+            extractor: self.extractor,
+            routes,
+            fallback,
+        }
+    }
+
+    /// Wrap the route registered for a key with a layer.
+    ///
+    /// Nothing happens if there is no route for the key.
+    pub fn layer_route<Marker, L>(mut self, key: &K, layer: &L) -> Self
+    where
+        L: ProcessorLayer<ArcProcessor<I, O>, Marker>,
+        L::Wrapped: Processor<I, O> + Send + Sync + 'static,
+    {
+        if let Some((key, processor)) = self.routes.remove_entry(key) {
+            self.routes.insert(key, processor.with_layer(layer));
+        }
+        self
+    }
+
+    /// Check whether a route is registered for a key.
+    pub fn has_route(&self, key: &K) -> bool {
+        self.routes.contains_key(key)
+    }
+}
+
+impl<K: Clone, I, O> Clone for Router<K, I, O> {
+    fn clone(&self) -> Self {
+        Self {
+            extractor: self.extractor.clone(),
+            routes: self.routes.clone(),
+            fallback: self.fallback.clone(),
+        }
+    }
+}
+
+impl<K: Debug, I, O> Debug for Router<K, I, O> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Router")
+            .field("extractor", &"Fn(&I) -> K")
+            .field("routes", &self.routes.keys().collect::<Vec<_>>())
+            .field("fallback", &matches!(self.fallback, Fallback::Processor(_)))
+            .finish()
+    }
+}
+
+impl<K: Eq + Hash + Send + Sync, I: Send, O: Send> Processor<I, O> for Router<K, I, O> {
+    fn process(&self, input: I) -> impl Future<Output = O> + Send {
+        let key = (self.extractor)(&input);
+        let future: BoxFuture<'_, O> = match (self.routes.get(&key), &self.fallback) {
+            (Some(processor), _) => processor.process_boxed(input),
+            (None, Fallback::Processor(processor)) => processor.process_boxed(input),
+            (None, Fallback::Error(f)) => {
+                let output = f(key);
+                Box::pin(async move { output })
+            }
+        };
+        future
+    }
+}
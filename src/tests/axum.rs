@@ -0,0 +1,118 @@
+use crate::axum::ProcessorHandler;
+use crate::flow::EarlyReturn;
+use crate::processor::{Processor, ProcessorExt};
+use axum::Json;
+use axum::body::Body;
+use axum::extract::{Query, Request};
+use axum::handler::Handler;
+use axum::http::StatusCode;
+use axum::http::header::CONTENT_TYPE;
+use axum::response::{IntoResponse, Response};
+
+#[derive(Debug, Clone, serde::Deserialize)]
+struct Greeting {
+    name: String,
+}
+
+#[derive(Debug, Clone)]
+struct Greet;
+
+impl Processor<Greeting, EarlyReturn<(StatusCode, &'static str), String>> for Greet {
+    async fn process(&self, input: Greeting) -> EarlyReturn<(StatusCode, &'static str), String> {
+        if input.name.is_empty() {
+            return EarlyReturn::Return((StatusCode::BAD_REQUEST, "empty name"));
+        }
+        EarlyReturn::Expr(format!("hello, {}", input.name))
+    }
+}
+
+async fn body_text(response: axum::response::Response) -> String {
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    String::from_utf8(bytes.to_vec()).unwrap()
+}
+
+fn json_request(body: &'static str) -> Request {
+    Request::builder()
+        .method("POST")
+        .header(CONTENT_TYPE, "application/json")
+        .body(Body::from(body))
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_json_handler() {
+    let handler = ProcessorHandler::new(Greet.map_input(|Json(body): Json<Greeting>| body));
+
+    let response = handler
+        .clone()
+        .call(json_request(r#"{"name":"kanau"}"#), ())
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(body_text(response).await, "hello, kanau");
+
+    let response = handler
+        .clone()
+        .call(json_request(r#"{"name":""}"#), ())
+        .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(body_text(response).await, "empty name");
+
+    let response = handler.call(json_request("not json"), ()).await;
+    assert!(response.status().is_client_error());
+}
+
+#[tokio::test]
+async fn test_query_handler() {
+    let handler = ProcessorHandler::new(Greet.map_input(|Query(query): Query<Greeting>| query));
+    let request = Request::builder()
+        .uri("/greet?name=haruki")
+        .body(Body::empty())
+        .unwrap();
+    let response = handler.call(request, ()).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(body_text(response).await, "hello, haruki");
+}
+
+#[derive(Debug, Clone)]
+struct Limit;
+
+impl Processor<Json<u32>, EarlyReturn<Response, Json<u32>>> for Limit {
+    async fn process(&self, Json(input): Json<u32>) -> EarlyReturn<Response, Json<u32>> {
+        if input > 10 {
+            return EarlyReturn::Return(
+                Response::builder()
+                    .status(StatusCode::PAYLOAD_TOO_LARGE)
+                    .body(Body::from("too large"))
+                    .unwrap(),
+            );
+        }
+        EarlyReturn::Expr(Json(input * 2))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Echo;
+
+impl Processor<Json<u32>, Response> for Echo {
+    async fn process(&self, input: Json<u32>) -> Response {
+        input.into_response()
+    }
+}
+
+#[tokio::test]
+async fn test_response_handler() {
+    let handler = ProcessorHandler::new(Limit);
+    let response = handler.clone().call(json_request("3"), ()).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(body_text(response).await, "6");
+
+    let response = handler.call(json_request("11"), ()).await;
+    assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    assert_eq!(body_text(response).await, "too large");
+
+    let handler = ProcessorHandler::new(Echo);
+    let response = handler.call(json_request("4"), ()).await;
+    assert_eq!(body_text(response).await, "4");
+}
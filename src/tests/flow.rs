@@ -0,0 +1,310 @@
+use crate::flow::{EarlyReturn, Validation};
+use crate::layer::stack::Identity;
+use crate::processor::{Processor, ProcessorExt, processor_fn};
+use std::ops::ControlFlow;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+fn half(x: u32) -> EarlyReturn<String, u32> {
+    if x.is_multiple_of(2) {
+        EarlyReturn::Expr(x / 2)
+    } else {
+        EarlyReturn::Return(format!("{x} is odd"))
+    }
+}
+
+#[test]
+fn test_control_flow_conversion() {
+    fn eighth(x: u32) -> ControlFlow<String, u32> {
+        let x = half(x).into_control_flow()?;
+        let x = half(x).into_control_flow()?;
+        half(x).into()
+    }
+
+    assert_eq!(EarlyReturn::from(eighth(16)), EarlyReturn::Expr(2));
+    assert_eq!(
+        EarlyReturn::from(eighth(12)),
+        EarlyReturn::Return("3 is odd".to_string())
+    );
+    assert_eq!(
+        ControlFlow::from(EarlyReturn::<u32, u32>::Return(1)),
+        ControlFlow::Break(1)
+    );
+}
+
+#[cfg(all(feature = "try_trait", kanau_nightly))]
+#[test]
+fn test_try_operator() {
+    fn quarter(x: u32) -> EarlyReturn<String, u32> {
+        let x = half(x)?;
+        half(x)
+    }
+
+    fn parse_half(s: &str) -> EarlyReturn<Result<u32, String>, u32> {
+        let x: u32 = s.parse().map_err(|_| format!("{s} is not a number"))?;
+        EarlyReturn::Expr(x / 2)
+    }
+
+    fn first_half(v: &[u32]) -> EarlyReturn<Option<u32>, u32> {
+        let x = v.first()?;
+        EarlyReturn::Expr(x / 2)
+    }
+
+    assert_eq!(quarter(8), EarlyReturn::Expr(2));
+    assert_eq!(quarter(6), EarlyReturn::Return("3 is odd".to_string()));
+    assert_eq!(parse_half("4"), EarlyReturn::Expr(2));
+    assert_eq!(
+        parse_half("x"),
+        EarlyReturn::Return(Err("x is not a number".to_string()))
+    );
+    assert_eq!(first_half(&[4]), EarlyReturn::Expr(2));
+    assert_eq!(first_half(&[]), EarlyReturn::Return(None));
+}
+
+#[test]
+fn test_result_and_option_conversion() {
+    assert_eq!(half(4).into_result(), Ok(2));
+    assert_eq!(half(3).into_result(), Err("3 is odd".to_string()));
+
+    let found: EarlyReturn<&str, u32> = EarlyReturn::from_option(Some(1), "missing");
+    assert_eq!(found, EarlyReturn::Expr(1));
+    let missing: EarlyReturn<&str, u32> = EarlyReturn::from_option(None, "missing");
+    assert_eq!(missing, EarlyReturn::Return("missing"));
+
+    let some: EarlyReturn<&str, Option<u32>> = EarlyReturn::Expr(Some(1));
+    let none: EarlyReturn<&str, Option<u32>> = EarlyReturn::Expr(None);
+    let ret: EarlyReturn<&str, Option<u32>> = EarlyReturn::Return("early");
+    assert_eq!(some.ok_or_return("none"), EarlyReturn::Expr(1));
+    assert_eq!(none.ok_or_return("none"), EarlyReturn::Return("none"));
+    assert_eq!(ret.ok_or_return("none"), EarlyReturn::Return("early"));
+    assert_eq!(some.transpose(), Some(EarlyReturn::Expr(1)));
+    assert_eq!(none.transpose(), None);
+    assert_eq!(ret.transpose(), Some(EarlyReturn::Return("early")));
+
+    let err: EarlyReturn<&str, Result<u32, u32>> = EarlyReturn::Expr(Err(0));
+    let ok: EarlyReturn<&str, Result<u32, u32>> = EarlyReturn::Expr(Ok(1));
+    assert_eq!(err.transpose(), Err(0));
+    assert_eq!(ok.transpose(), Ok(EarlyReturn::Expr(1)));
+}
+
+#[test]
+fn test_iterator_helpers() {
+    let sum = EarlyReturn::try_fold([1, 2, 3], 0, |acc, x| {
+        if acc > 2 {
+            EarlyReturn::Return(format!("too large before {x}"))
+        } else {
+            EarlyReturn::Expr(acc + x)
+        }
+    });
+    assert_eq!(sum, EarlyReturn::Return("too large before 3".to_string()));
+
+    let halves: EarlyReturn<String, Vec<u32>> = [2, 4, 6].into_iter().map(half).collect();
+    assert_eq!(halves, EarlyReturn::Expr(vec![1, 2, 3]));
+
+    let halves: EarlyReturn<String, Vec<u32>> = [2, 3, 5].into_iter().map(half).collect();
+    assert_eq!(halves, EarlyReturn::Return("3 is odd".to_string()));
+}
+
+#[derive(Debug, Clone)]
+struct HalfStep;
+
+impl Processor<u32, EarlyReturn<String, u32>> for HalfStep {
+    async fn process(&self, input: u32) -> EarlyReturn<String, u32> {
+        half(input)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Split;
+
+impl Processor<u32, EarlyReturn<String, (u32, u32)>> for Split {
+    async fn process(&self, input: u32) -> EarlyReturn<String, (u32, u32)> {
+        EarlyReturn::Expr((input / 10, input % 10))
+    }
+}
+
+#[tokio::test]
+async fn test_flow_macro() {
+    async fn run(input: u32) -> EarlyReturn<String, u32> {
+        crate::flow! {
+            let x <- HalfStep.process(input).await;
+            let (tens, ones) <- Split.process(x).await;
+            let mut sum = tens + ones;
+            sum *= 2;
+            let _ <- half(sum / 2);
+            HalfStep.process(sum).await
+        }
+    }
+
+    assert_eq!(run(48).await, EarlyReturn::Expr(6));
+    assert_eq!(run(7).await, EarlyReturn::Return("7 is odd".to_string()));
+    assert_eq!(run(46).await, EarlyReturn::Return("5 is odd".to_string()));
+}
+
+#[test]
+fn test_flow_macro_with_result() {
+    fn run(input: &str) -> EarlyReturn<Result<u32, String>, u32> {
+        crate::flow! {
+            let mut x <- input.parse::<u32>().map_err(|e| e.to_string());
+            x += 1;
+            let y <- EarlyReturn::from_option(x.checked_sub(10), Ok(0));
+            yield y
+        }
+    }
+
+    assert_eq!(run("11"), EarlyReturn::Expr(2));
+    assert_eq!(run("1"), EarlyReturn::Return(Ok(0)));
+    assert!(matches!(run("x"), EarlyReturn::Return(Err(_))));
+}
+
+#[derive(Debug, Clone, Default)]
+struct CountedHalf {
+    calls: Arc<AtomicUsize>,
+}
+
+impl Processor<u32, EarlyReturn<String, u32>> for CountedHalf {
+    async fn process(&self, input: u32) -> EarlyReturn<String, u32> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        half(input)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Describe;
+
+impl Processor<u32, EarlyReturn<String, String>> for Describe {
+    async fn process(&self, input: u32) -> EarlyReturn<String, String> {
+        EarlyReturn::Expr(format!("got {input}"))
+    }
+}
+
+#[tokio::test]
+async fn test_cps_pipeline() {
+    let step = CountedHalf::default();
+    let pipeline = crate::cps_pipeline!(step.clone(), step.clone(), step.clone(), Describe,);
+    assert_eq!(
+        pipeline.process(16).await,
+        EarlyReturn::Expr("got 2".to_string())
+    );
+    assert_eq!(step.calls.load(Ordering::SeqCst), 3);
+
+    // the first return stops the pipeline
+    assert_eq!(
+        pipeline.process(10).await,
+        EarlyReturn::Return("5 is odd".to_string())
+    );
+    assert_eq!(step.calls.load(Ordering::SeqCst), 5);
+
+    // pipelines are steps too, and can be wrapped by layers
+    let nested = crate::cps_pipeline!(
+        step.clone(),
+        crate::cps_pipeline!(step.clone(), Describe).with_layer(&Identity),
+    );
+    assert_eq!(
+        nested.process(8).await,
+        EarlyReturn::Expr("got 2".to_string())
+    );
+    assert_eq!(
+        crate::cps_pipeline!(Describe).process(1).await,
+        EarlyReturn::Expr("got 1".to_string())
+    );
+}
+
+fn positive(field: &'static str, value: i32) -> Validation<String, u32> {
+    if value > 0 {
+        Validation::valid(value as u32)
+    } else {
+        Validation::invalid(format!("{field} must be positive"))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct CheckPositive;
+
+impl Processor<i32, Validation<String, u32>> for CheckPositive {
+    async fn process(&self, input: i32) -> Validation<String, u32> {
+        positive("item", input)
+    }
+}
+
+#[test]
+fn test_validation_combinators() {
+    let size = positive("width", 2).zip_with(positive("height", 3), |w, h| w * h);
+    assert_eq!(size, Validation::Valid(6));
+
+    let size = positive("width", 0).zip(positive("height", -1));
+    assert_eq!(
+        size,
+        Validation::Invalid(vec![
+            "width must be positive".to_string(),
+            "height must be positive".to_string(),
+        ])
+    );
+
+    let checked = positive("a", 1).and(positive("b", 0)).and(positive("c", 2));
+    assert_eq!(
+        checked,
+        Validation::invalid("b must be positive".to_string())
+    );
+
+    let all = Validation::traverse([1, -2, 3, -4], |x| positive("item", x));
+    assert_eq!(all.map_err(|e| e.len()).into_result(), Err(vec![21, 21]));
+    assert_eq!(
+        Validation::traverse([1, 2], |x| positive("item", x)),
+        Validation::Valid(vec![1, 2])
+    );
+
+    let empty: Validation<String, Vec<u32>> = [Validation::Valid(1), Validation::Invalid(vec![])]
+        .into_iter()
+        .collect();
+    assert_eq!(empty, Validation::Invalid(vec![]));
+
+    let parsed: Validation<std::num::ParseIntError, u32> = "12".parse().into();
+    assert!(parsed.is_valid());
+}
+
+#[tokio::test]
+async fn test_validation_async_and_flow() {
+    let all = Validation::process_traverse([3, 1, 2], &CheckPositive, 2).await;
+    assert_eq!(all, Validation::Valid(vec![3, 1, 2]));
+    let count = all
+        .process_map(&processor_fn(|v: Vec<u32>| async move { v.len() }))
+        .await;
+    assert_eq!(count, Validation::Valid(3));
+
+    let all = Validation::process_traverse([0, 1, -1], &CheckPositive, 2).await;
+    assert_eq!(
+        all.clone().into_early_return(),
+        EarlyReturn::Return(vec!["item must be positive".to_string(); 2])
+    );
+
+    let sum: EarlyReturn<Vec<String>, u32> = crate::flow! {
+        let values <- Validation::process_traverse([1, 2, 3], &CheckPositive, 2).await;
+        yield values.iter().sum()
+    };
+    assert_eq!(sum, EarlyReturn::Expr(6));
+}
+
+#[derive(Debug, Default)]
+struct SlowCheck {
+    current: AtomicUsize,
+    max: AtomicUsize,
+}
+
+impl Processor<i32, Validation<String, u32>> for SlowCheck {
+    async fn process(&self, input: i32) -> Validation<String, u32> {
+        let now = self.current.fetch_add(1, Ordering::SeqCst) + 1;
+        self.max.fetch_max(now, Ordering::SeqCst);
+        tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        self.current.fetch_sub(1, Ordering::SeqCst);
+        positive("item", input)
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_validation_process_traverse_limit() {
+    let check = SlowCheck::default();
+    let all = Validation::process_traverse(1..=20, &check, 3).await;
+    assert_eq!(all, Validation::Valid((1..=20).collect()));
+    assert_eq!(check.max.load(Ordering::SeqCst), 3);
+}
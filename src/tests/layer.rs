@@ -0,0 +1,452 @@
+use crate::layer::circuit_breaker::{
+    CircuitBreakerConfig, CircuitBreakerLayer, CircuitOpenError, CircuitState,
+};
+use crate::layer::limit::{ConcurrencyLimitError, ConcurrencyLimitLayer, RateLimitLayer};
+use crate::layer::retry::{
+    ExponentialBackoff, FixedDelay, MaxAttempts, MaxElapsed, RetryIf, RetryLayer, RetryPolicy,
+    RetryState,
+};
+use crate::layer::stack::{Identity, LayerBuilder, Stack};
+use crate::layer::timeout::{TimeoutLayer, TimeoutProcessor, TimeoutProcessorLayer};
+use crate::layer::{Layer, LayerFn, LayerWrappedProcessor, ProcessorLayer};
+use crate::processor::{Processor, ProcessorExt, TryProcessor};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use tokio::sync::Semaphore;
+use tokio::time::Instant;
+use tokio::time::error::Elapsed;
+
+#[derive(Debug, Clone)]
+struct Sleep;
+
+impl Processor<u64, u64> for Sleep {
+    async fn process(&self, input: u64) -> u64 {
+        tokio::time::sleep(Duration::from_millis(input)).await;
+        input
+    }
+}
+
+#[derive(Debug, Clone)]
+struct FallibleSleep;
+
+impl Processor<u64, Result<u64, String>> for FallibleSleep {
+    async fn process(&self, input: u64) -> Result<u64, String> {
+        tokio::time::sleep(Duration::from_millis(input)).await;
+        Ok(input)
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_timeout_processor() {
+    let processor = TimeoutProcessor::new(Sleep, Duration::from_millis(100));
+    assert_eq!(processor.process(50).await.unwrap(), 50);
+    assert!(processor.process(150).await.is_err());
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_timeout_layer() {
+    let layer = TimeoutLayer::with_conversion(Duration::from_millis(100), |_: Elapsed| {
+        "timeout".to_string()
+    });
+    let processor = LayerWrappedProcessor::new(layer, FallibleSleep);
+    assert_eq!(processor.process(99).await, Ok(99));
+    assert_eq!(processor.process(101).await, Err("timeout".to_string()));
+
+    let processor = LayerWrappedProcessor::new(
+        TimeoutLayer::new(Duration::from_millis(100)),
+        FallibleSleep.map_err(anyhow::Error::msg),
+    );
+    let err = processor.process(200).await.unwrap_err();
+    assert!(err.downcast_ref::<Elapsed>().is_some());
+}
+
+#[derive(Debug, Default)]
+struct FailTimes {
+    fail_times: usize,
+    calls: Arc<AtomicUsize>,
+}
+
+impl FailTimes {
+    fn new(fail_times: usize) -> Self {
+        Self {
+            fail_times,
+            calls: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+}
+
+impl Processor<u32, Result<u32, String>> for FailTimes {
+    async fn process(&self, input: u32) -> Result<u32, String> {
+        let call = self.calls.fetch_add(1, Ordering::SeqCst);
+        if call < self.fail_times {
+            Err(format!("failure {call}"))
+        } else {
+            Ok(input)
+        }
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_retry_fixed_delay() {
+    let layer = RetryLayer::new(MaxAttempts::new(FixedDelay::new(Duration::from_secs(1)), 5));
+    let processor = LayerWrappedProcessor::new(layer.clone(), FailTimes::new(3));
+    let start = Instant::now();
+    assert_eq!(processor.process(7).await, Ok(7));
+    assert_eq!(start.elapsed(), Duration::from_secs(3));
+
+    let processor = LayerWrappedProcessor::new(layer, FailTimes::new(10));
+    assert_eq!(processor.process(7).await, Err("failure 4".to_string()));
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_retry_exponential_backoff() {
+    let policy = MaxElapsed::new(
+        ExponentialBackoff::new(Duration::from_millis(100), Duration::from_millis(500)),
+        Duration::from_secs(2),
+    );
+    let processor = LayerWrappedProcessor::new(RetryLayer::new(policy), FailTimes::new(100));
+    let start = Instant::now();
+    assert!(processor.process(1).await.is_err());
+    // 100 + 200 + 400 + 500 + 500 = 1700, the next 500ms would exceed 2s
+    assert_eq!(start.elapsed(), Duration::from_millis(1700));
+}
+
+#[test]
+fn test_exponential_backoff_jitter() {
+    let policy = ExponentialBackoff::new(Duration::from_millis(100), Duration::from_secs(1))
+        .with_jitter(true);
+    for attempt in 1..10 {
+        let state = RetryState {
+            attempt,
+            elapsed: Duration::ZERO,
+        };
+        let delay = RetryPolicy::<()>::next_delay(&policy, &(), &state).unwrap();
+        assert!(delay <= Duration::from_millis(100 << (attempt - 1)).min(Duration::from_secs(1)));
+    }
+}
+
+#[test]
+fn test_max_elapsed_huge_delay() {
+    let state = RetryState {
+        attempt: 1,
+        elapsed: Duration::from_secs(1),
+    };
+    let policy = MaxElapsed::new(FixedDelay::new(Duration::MAX), Duration::from_secs(10));
+    assert_eq!(RetryPolicy::<()>::next_delay(&policy, &(), &state), None);
+
+    // the exponent saturates to `max_delay`
+    let policy = MaxElapsed::new(
+        ExponentialBackoff::new(Duration::from_secs(1), Duration::MAX),
+        Duration::MAX,
+    );
+    let state = RetryState {
+        attempt: u32::MAX,
+        ..state
+    };
+    assert_eq!(RetryPolicy::<()>::next_delay(&policy, &(), &state), None);
+
+    // the jitter doesn't overflow either
+    let policy = ExponentialBackoff::new(Duration::from_secs(1), Duration::MAX).with_jitter(true);
+    assert!(RetryPolicy::<()>::next_delay(&policy, &(), &state).is_some());
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_retry_if() {
+    let policy = RetryIf::new(FixedDelay::new(Duration::from_secs(1)), |e: &String| {
+        e != "failure 1"
+    });
+    let inner = FailTimes::new(5);
+    let calls = inner.calls.clone();
+    let processor = LayerWrappedProcessor::new(RetryLayer::new(policy), inner);
+    assert_eq!(processor.process(1).await, Err("failure 1".to_string()));
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+}
+
+#[derive(Debug, PartialEq)]
+enum RemoteError {
+    Remote,
+    CircuitOpen,
+}
+
+impl From<CircuitOpenError> for RemoteError {
+    fn from(_: CircuitOpenError) -> Self {
+        RemoteError::CircuitOpen
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Remote;
+
+impl Processor<bool, Result<(), RemoteError>> for Remote {
+    async fn process(&self, healthy: bool) -> Result<(), RemoteError> {
+        if healthy {
+            Ok(())
+        } else {
+            Err(RemoteError::Remote)
+        }
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_circuit_breaker() {
+    let transitions = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let transitions_clone = transitions.clone();
+    let layer = CircuitBreakerConfig::new(0.5, 4, Duration::from_secs(10))
+        .on_state_change(move |from, to| {
+            transitions_clone.lock().unwrap().push((from, to));
+        })
+        .build();
+    let processor = LayerWrappedProcessor::new(layer.clone(), Remote);
+    let shared = LayerWrappedProcessor::new(layer.clone(), Remote);
+
+    assert_eq!(processor.process(true).await, Ok(()));
+    assert_eq!(processor.process(false).await, Err(RemoteError::Remote));
+    assert_eq!(processor.process(true).await, Ok(()));
+    assert_eq!(layer.state(), CircuitState::Closed);
+    assert_eq!(shared.process(false).await, Err(RemoteError::Remote));
+    assert_eq!(layer.state(), CircuitState::Open);
+
+    // the clones share the same circuit
+    assert_eq!(processor.process(true).await, Err(RemoteError::CircuitOpen));
+    assert_eq!(shared.process(true).await, Err(RemoteError::CircuitOpen));
+
+    // a failed trial opens the circuit again
+    tokio::time::advance(Duration::from_secs(10)).await;
+    assert_eq!(processor.process(false).await, Err(RemoteError::Remote));
+    assert_eq!(layer.state(), CircuitState::Open);
+
+    // a successful trial closes the circuit
+    tokio::time::advance(Duration::from_secs(10)).await;
+    assert_eq!(shared.process(true).await, Ok(()));
+    assert_eq!(layer.state(), CircuitState::Closed);
+
+    assert_eq!(
+        *transitions.lock().unwrap(),
+        vec![
+            (CircuitState::Closed, CircuitState::Open),
+            (CircuitState::Open, CircuitState::HalfOpen),
+            (CircuitState::HalfOpen, CircuitState::Open),
+            (CircuitState::Open, CircuitState::HalfOpen),
+            (CircuitState::HalfOpen, CircuitState::Closed),
+        ]
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_circuit_breaker_config() {
+    let config = CircuitBreakerConfig::new(1.0, 4, Duration::from_secs(10))
+        .with_minimum_calls(2)
+        .with_half_open_calls(2);
+    let layer: CircuitBreakerLayer = config.clone().build();
+    let processor = LayerWrappedProcessor::new(layer.clone(), Remote);
+
+    assert_eq!(processor.process(false).await, Err(RemoteError::Remote));
+    assert_eq!(layer.state(), CircuitState::Closed);
+    assert_eq!(processor.process(false).await, Err(RemoteError::Remote));
+    assert_eq!(layer.state(), CircuitState::Open);
+
+    // every build creates a new circuit
+    assert_eq!(config.build().state(), CircuitState::Closed);
+
+    tokio::time::advance(Duration::from_secs(10)).await;
+    assert_eq!(processor.process(true).await, Ok(()));
+    assert_eq!(layer.state(), CircuitState::HalfOpen);
+    assert_eq!(processor.process(true).await, Ok(()));
+    assert_eq!(layer.state(), CircuitState::Closed);
+}
+
+#[tokio::test]
+async fn test_circuit_breaker_threshold_edge_cases() {
+    // a zero threshold opens on the first failure, not on successes
+    let layer = CircuitBreakerLayer::new(0.0, 2, Duration::from_secs(10));
+    let processor = LayerWrappedProcessor::new(layer.clone(), Remote);
+    assert_eq!(processor.process(true).await, Ok(()));
+    assert_eq!(processor.process(true).await, Ok(()));
+    assert_eq!(layer.state(), CircuitState::Closed);
+    assert_eq!(processor.process(false).await, Err(RemoteError::Remote));
+    assert_eq!(layer.state(), CircuitState::Open);
+
+    // NaN is treated as `1.0`
+    let layer = CircuitBreakerLayer::new(f64::NAN, 2, Duration::from_secs(10));
+    let processor = LayerWrappedProcessor::new(layer.clone(), Remote);
+    assert_eq!(processor.process(false).await, Err(RemoteError::Remote));
+    assert_eq!(processor.process(false).await, Err(RemoteError::Remote));
+    assert_eq!(layer.state(), CircuitState::Open);
+}
+
+#[derive(Debug, Clone, Default)]
+struct InFlightCounter {
+    current: Arc<AtomicUsize>,
+    max: Arc<AtomicUsize>,
+}
+
+impl Processor<u64, u64> for InFlightCounter {
+    async fn process(&self, input: u64) -> u64 {
+        let now = self.current.fetch_add(1, Ordering::SeqCst) + 1;
+        self.max.fetch_max(now, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(input)).await;
+        self.current.fetch_sub(1, Ordering::SeqCst);
+        input
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_concurrency_limit_shared() {
+    let layer = ConcurrencyLimitLayer::new(2);
+    let counter = InFlightCounter::default();
+    let first = LayerWrappedProcessor::new(layer.clone(), counter.clone());
+    let second = LayerWrappedProcessor::new(layer.clone(), counter.clone());
+    futures::join!(
+        first.process(1),
+        first.process(2),
+        second.process(3),
+        second.process(4),
+    );
+    assert_eq!(counter.max.load(Ordering::SeqCst), 2);
+    assert_eq!(layer.available_permits(), 2);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_concurrency_limit_edge_cases() {
+    // a limit of zero would block every call forever
+    let layer = ConcurrencyLimitLayer::new(0);
+    assert_eq!(layer.available_permits(), 1);
+    let counter = InFlightCounter::default();
+    let processor = LayerWrappedProcessor::new(layer, counter.clone());
+    futures::join!(processor.process(1), processor.process(2));
+    assert_eq!(counter.max.load(Ordering::SeqCst), 1);
+
+    // a closed semaphore lifts the limit
+    let semaphore = Arc::new(Semaphore::new(1));
+    semaphore.close();
+    let counter = InFlightCounter::default();
+    let processor = LayerWrappedProcessor::new(
+        ConcurrencyLimitLayer::from_semaphore(semaphore),
+        counter.clone(),
+    );
+    futures::join!(processor.process(1), processor.process(2));
+    assert_eq!(counter.max.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_concurrency_limit_rejecting() {
+    #[derive(Debug, PartialEq)]
+    struct Busy;
+
+    impl From<ConcurrencyLimitError> for Busy {
+        fn from(_: ConcurrencyLimitError) -> Self {
+            Busy
+        }
+    }
+
+    let layer = ConcurrencyLimitLayer::new(1).rejecting();
+    let processor = LayerWrappedProcessor::new(layer, Sleep.map(Ok::<u64, Busy>));
+    let (a, b) = futures::join!(processor.process(10), processor.process(10));
+    assert_eq!(a, Ok(10));
+    assert_eq!(b, Err(Busy));
+    assert_eq!(processor.process(10).await, Ok(10));
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_rate_limit() {
+    let layer = RateLimitLayer::new(2, 1, Duration::from_secs(1));
+    let first = LayerWrappedProcessor::new(layer.clone(), Sleep);
+    let second = LayerWrappedProcessor::new(layer, Sleep);
+    let start = Instant::now();
+    first.process(0).await;
+    second.process(0).await;
+    assert_eq!(start.elapsed(), Duration::ZERO);
+    first.process(0).await;
+    second.process(0).await;
+    assert_eq!(start.elapsed(), Duration::from_secs(2));
+}
+
+#[derive(Debug, Clone)]
+struct Record {
+    name: &'static str,
+    log: Arc<std::sync::Mutex<Vec<String>>>,
+}
+
+impl<I: Send, O: Send, P: Processor<I, O> + Sync> Layer<I, O, P> for Record {
+    async fn wrap(&self, processor: &P, input: I) -> O {
+        self.log.lock().unwrap().push(format!("{} in", self.name));
+        let output = processor.process(input).await;
+        self.log.lock().unwrap().push(format!("{} out", self.name));
+        output
+    }
+}
+
+#[tokio::test]
+async fn test_layer_builder_order() {
+    let log = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let record = |name| Record {
+        name,
+        log: log.clone(),
+    };
+    let processor = LayerBuilder::new()
+        .layer(record("a"))
+        .layer(record("b"))
+        .layer(record("c"))
+        .processor(Sleep);
+    assert_eq!(processor.process(1).await, 1);
+    assert_eq!(
+        *log.lock().unwrap(),
+        vec!["a in", "b in", "c in", "c out", "b out", "a out"]
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_layer_builder_timeout_and_retry() {
+    let timeout = TimeoutLayer::with_conversion(Duration::from_millis(1500), |_: Elapsed| {
+        "timeout".to_string()
+    });
+    let retry = RetryLayer::new(MaxAttempts::new(FixedDelay::new(Duration::from_secs(1)), 5));
+
+    // the timeout covers all the attempts
+    let processor = LayerBuilder::new()
+        .layer(timeout.clone())
+        .layer(retry.clone())
+        .processor(FailTimes::new(3));
+    assert_eq!(processor.process(1).await, Err("timeout".to_string()));
+
+    // the timeout applies to every attempt
+    let processor = LayerBuilder::new()
+        .layer(retry)
+        .layer(timeout)
+        .processor(FailTimes::new(3));
+    assert_eq!(processor.process(1).await, Ok(1));
+
+    let stacked = Stack::new(Identity, Identity);
+    let processor = LayerWrappedProcessor::new(stacked, Sleep);
+    assert_eq!(processor.process(0).await, 0);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_processor_layer() {
+    // a processor layer can change the output type
+    let timeout = TimeoutLayer::<fn(Elapsed) -> Elapsed>::new(Duration::from_millis(100));
+    let processor = Sleep.with_layer(&timeout.processor_layer());
+    assert_eq!(processor.process(10).await.unwrap(), 10);
+    assert!(processor.process(200).await.is_err());
+
+    // so can a layer function
+    let timeout = LayerFn::new(|p| TimeoutProcessor::new(p, Duration::from_millis(100)));
+    let processor = Sleep.with_layer(&timeout);
+    assert!(processor.process(200).await.is_err());
+
+    let processor = Sleep.with_layer(&TimeoutProcessorLayer::new(Duration::from_millis(100)));
+    assert_eq!(processor.process(20).await, Ok(20));
+
+    // every clonable layer is a processor layer
+    let retry = RetryLayer::new(MaxAttempts::new(FixedDelay::new(Duration::from_secs(1)), 5));
+    let processor = FailTimes::new(2).with_layer(&retry);
+    assert_eq!(processor.process(3).await, Ok(3));
+
+    fn apply<P, Marker, L: ProcessorLayer<P, Marker>>(layer: &L, processor: P) -> L::Wrapped {
+        layer.layer(processor)
+    }
+    let stacked = LayerBuilder::new().layer(retry).into_inner();
+    let processor = apply(&stacked, FailTimes::new(1));
+    assert_eq!(processor.process(4).await, Ok(4));
+}
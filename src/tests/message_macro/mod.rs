@@ -1,4 +1,3 @@
-
 #[cfg(all(feature = "serde_json", feature = "message"))]
 mod serde_json_macro_inner;
 
@@ -10,5 +9,9 @@ mod rkyv_macro_inner;
 
 #[cfg(all(feature = "bincode", feature = "message"))]
 mod bincode_macro;
+
+#[cfg(all(feature = "rkyv", feature = "message"))]
 mod rkyv_macro;
+
+#[cfg(all(feature = "serde_json", feature = "message"))]
 mod serde_json_macro;
@@ -1,3 +1,14 @@
 #![allow(clippy::unwrap_used)]
 
-mod message_macro;
\ No newline at end of file
+#[cfg(feature = "axum")]
+mod axum;
+
+mod flow;
+mod layer;
+mod message_macro;
+mod processor;
+mod processor_macro;
+mod router;
+
+#[cfg(feature = "tower")]
+mod tower;
@@ -0,0 +1,437 @@
+use crate::layer::LayerFn;
+use crate::layer::stack::Identity;
+use crate::processor::{
+    BoundRefProcessor, BoxProcessor, FinalAsProcessor, FinalProcessor, Processor, ProcessorAsFinal,
+    ProcessorExt, RefProcessor, SpawnError, TryProcessor, final_processor_fn,
+    parallel_map_borrowed_bounded, parallel_map_bounded, parallel_map_enumerate,
+    parallel_map_ordered, parallel_map_partition, parallel_map_stream, parallel_map_stream_ordered,
+    parallel_spawn_map, processor_fn, ref_processor_fn, spawn_process, try_parallel_map,
+};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tokio_stream::StreamExt;
+
+#[derive(Debug, Clone)]
+struct AddOne;
+
+impl Processor<u32, u32> for AddOne {
+    async fn process(&self, input: u32) -> u32 {
+        input + 1
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Double;
+
+impl Processor<u32, u32> for Double {
+    async fn process(&self, input: u32) -> u32 {
+        input * 2
+    }
+}
+
+#[tokio::test]
+async fn test_map_and_then() {
+    let pipeline = AddOne.and_then(Double).map(|x| x.to_string());
+    assert_eq!(pipeline.process(1).await, "4");
+
+    let cloned = pipeline.clone();
+    assert_eq!(cloned.process(2).await, "6");
+}
+
+#[tokio::test]
+async fn test_map_async_and_map_input() {
+    let pipeline = AddOne
+        .map_input(|s: &str| s.len() as u32)
+        .map_async(|x| async move { x * 10 });
+    assert_eq!(pipeline.process("abc").await, 40);
+}
+
+#[tokio::test]
+async fn test_inspect() {
+    let seen = Arc::new(AtomicUsize::new(0));
+    let seen_clone = seen.clone();
+    let pipeline = Double.inspect(move |x| {
+        seen_clone.store(*x as usize, Ordering::SeqCst);
+    });
+    assert_eq!(pipeline.process(21).await, 42);
+    assert_eq!(seen.load(Ordering::SeqCst), 42);
+}
+
+#[tokio::test]
+async fn test_boxed() {
+    let processors = [
+        AddOne.boxed(),
+        Double.boxed(),
+        AddOne.and_then(Double).boxed(),
+    ];
+    let mut outputs = Vec::new();
+    for processor in processors.iter() {
+        outputs.push(processor.process(3).await);
+    }
+    assert_eq!(outputs, vec![4, 6, 8]);
+}
+
+#[tokio::test]
+async fn test_shared_and_layered_erased_processors() {
+    let shared = AddOne.and_then(Double).shared();
+    let cloned = shared.clone();
+    assert_eq!(shared.process(1).await, 4);
+    assert_eq!(cloned.process_boxed(2).await, 6);
+
+    let times_ten = LayerFn::new(|p: BoxProcessor<u32, u32>| p.map(|x| x * 10));
+    let layered = AddOne.boxed().with_layer(&times_ten).with_layer(&Identity);
+    assert_eq!(layered.process(1).await, 20);
+
+    let layered = layered.into_shared().with_layer(&Identity);
+    assert_eq!(layered.clone().process(2).await, 30);
+}
+
+#[derive(Debug, Clone)]
+struct ParseNumber;
+
+impl Processor<&'static str, Result<u32, String>> for ParseNumber {
+    async fn process(&self, input: &'static str) -> Result<u32, String> {
+        input.parse().map_err(|_| format!("not a number: {input}"))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct NonZero;
+
+impl Processor<u32, Result<u32, &'static str>> for NonZero {
+    async fn process(&self, input: u32) -> Result<u32, &'static str> {
+        if input == 0 { Err("zero") } else { Ok(input) }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Recover;
+
+impl Processor<String, Result<u32, usize>> for Recover {
+    async fn process(&self, input: String) -> Result<u32, usize> {
+        if input.ends_with("?") {
+            Ok(0)
+        } else {
+            Err(input.len())
+        }
+    }
+}
+
+#[derive(Debug)]
+struct WrappedError(String);
+
+impl From<String> for WrappedError {
+    fn from(e: String) -> Self {
+        WrappedError(e)
+    }
+}
+
+#[tokio::test]
+async fn test_map_ok_and_map_err() {
+    let pipeline = ParseNumber.map_ok(|x| x + 1).map_err(|e| e.len());
+    assert_eq!(pipeline.process("41").await, Ok(42));
+    assert_eq!(pipeline.process("x").await, Err(15));
+}
+
+#[tokio::test]
+async fn test_and_then_ok() {
+    let pipeline = ParseNumber.and_then_ok(NonZero.map_err(|e: &str| e.to_string()));
+    assert_eq!(pipeline.process("7").await, Ok(7));
+    assert_eq!(pipeline.process("0").await, Err("zero".to_string()));
+    assert_eq!(
+        pipeline.process("a").await,
+        Err("not a number: a".to_string())
+    );
+}
+
+#[tokio::test]
+async fn test_or_else_and_unwrap_or_else() {
+    let pipeline = ParseNumber.or_else(Recover);
+    assert_eq!(pipeline.process("3").await, Ok(3));
+    assert_eq!(pipeline.process("?").await, Ok(0));
+    assert_eq!(pipeline.process("ab").await, Err(16));
+
+    let infallible = ParseNumber.unwrap_or_else(|_| u32::MAX);
+    assert_eq!(infallible.process("ab").await, u32::MAX);
+}
+
+#[tokio::test]
+async fn test_err_into() {
+    let pipeline = ParseNumber.err_into::<WrappedError>();
+    let err = pipeline.process("z").await.unwrap_err();
+    assert_eq!(err.0, "not a number: z");
+}
+
+#[derive(Debug, Default)]
+struct InFlightCounter {
+    current: AtomicUsize,
+    max: AtomicUsize,
+}
+
+impl Processor<u32, u32> for InFlightCounter {
+    async fn process(&self, input: u32) -> u32 {
+        let now = self.current.fetch_add(1, Ordering::SeqCst) + 1;
+        self.max.fetch_max(now, Ordering::SeqCst);
+        tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        self.current.fetch_sub(1, Ordering::SeqCst);
+        input
+    }
+}
+
+impl FinalProcessor<u32, u32> for InFlightCounter {
+    async fn process(state: Arc<Self>, input: u32) -> u32 {
+        Processor::process(state.as_ref(), input).await
+    }
+}
+
+#[derive(Debug, Default)]
+struct SharedInFlightCounter(Arc<InFlightCounter>);
+
+impl RefProcessor<u32, u32> for SharedInFlightCounter {
+    fn process<'a, 'b>(
+        &'a self,
+        deps: &'b u32,
+        _input: (),
+    ) -> impl Future<Output = u32> + Send + 'a + 'b
+    where
+        (): 'a + 'b,
+    {
+        let counter = self.0.clone();
+        let input = *deps;
+        async move { Processor::process(counter.as_ref(), input).await }
+    }
+}
+
+#[tokio::test]
+async fn test_parallel_map_bounded() {
+    let counter = InFlightCounter::default();
+    let mut outputs: Vec<u32> = parallel_map_bounded(0..20, &counter, 3).collect().await;
+    outputs.sort();
+    assert_eq!(outputs, (0..20).collect::<Vec<_>>());
+    assert_eq!(counter.max.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn test_parallel_map_borrowed_bounded() {
+    let counter = SharedInFlightCounter::default();
+    let inputs: Vec<u32> = (0..10).collect();
+    let mut outputs: Vec<u32> = parallel_map_borrowed_bounded(inputs.iter(), &counter, 4)
+        .collect()
+        .await;
+    outputs.sort();
+    assert_eq!(outputs, inputs);
+    assert_eq!(counter.0.max.load(Ordering::SeqCst), 4);
+}
+
+#[derive(Debug, Clone)]
+struct ReverseDelay;
+
+impl Processor<u64, u64> for ReverseDelay {
+    async fn process(&self, input: u64) -> u64 {
+        tokio::time::sleep(std::time::Duration::from_millis(20 - input * 2)).await;
+        input
+    }
+}
+
+#[tokio::test]
+async fn test_parallel_map_ordered() {
+    let outputs: Vec<u64> = parallel_map_ordered(0..10, &ReverseDelay, 4)
+        .collect()
+        .await;
+    assert_eq!(outputs, (0..10).collect::<Vec<_>>());
+}
+
+#[tokio::test]
+async fn test_parallel_map_enumerate() {
+    let inputs = vec![5, 3, 9, 1];
+    let mut outputs: Vec<(usize, u64)> =
+        parallel_map_enumerate(inputs.clone().into_iter(), &ReverseDelay, 4)
+            .collect()
+            .await;
+    assert_ne!(outputs.first().map(|x| x.0), Some(0));
+    outputs.sort();
+    let expected: Vec<(usize, u64)> = inputs.into_iter().enumerate().collect();
+    assert_eq!(outputs, expected);
+
+    let counter = InFlightCounter::default();
+    let mut outputs: Vec<(usize, u32)> = parallel_map_enumerate(0..20, &counter, 3).collect().await;
+    outputs.sort();
+    assert_eq!(outputs, (0..20).enumerate().collect::<Vec<_>>());
+    assert_eq!(counter.max.load(Ordering::SeqCst), 3);
+}
+
+#[derive(Debug, Default)]
+struct FailOnOdd {
+    finished: AtomicUsize,
+}
+
+impl Processor<u64, Result<u64, u64>> for FailOnOdd {
+    async fn process(&self, input: u64) -> Result<u64, u64> {
+        if input % 2 == 1 {
+            return Err(input);
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        self.finished.fetch_add(1, Ordering::SeqCst);
+        Ok(input)
+    }
+}
+
+#[tokio::test]
+async fn test_try_parallel_map() {
+    let processor = FailOnOdd::default();
+    let mut outputs = try_parallel_map([0, 2, 4].into_iter(), &processor, 4)
+        .await
+        .unwrap();
+    outputs.sort();
+    assert_eq!(outputs, vec![0, 2, 4]);
+
+    let processor = FailOnOdd::default();
+    let result = try_parallel_map([0, 2, 3, 4].into_iter(), &processor, 4).await;
+    assert_eq!(result, Err(3));
+    assert_eq!(processor.finished.load(Ordering::SeqCst), 0);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_try_parallel_map_bounded() {
+    // the inputs after the error are never processed
+    let processor = FailOnOdd::default();
+    let result = try_parallel_map([0, 2, 3, 4].into_iter(), &processor, 1).await;
+    assert_eq!(result, Err(3));
+    assert_eq!(processor.finished.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_parallel_map_partition_bounded() {
+    // two at a time, the even inputs take 10ms each: (0, 2), then (4)
+    let start = tokio::time::Instant::now();
+    let (outputs, errors) = parallel_map_partition(0..6, &FailOnOdd::default(), 2).await;
+    assert_eq!((outputs.len(), errors.len()), (3, 3));
+    assert_eq!(start.elapsed(), std::time::Duration::from_millis(20));
+}
+
+#[tokio::test]
+async fn test_parallel_map_partition() {
+    let processor = FailOnOdd::default();
+    let (mut outputs, mut errors) = parallel_map_partition(0..6, &processor, 6).await;
+    outputs.sort();
+    errors.sort();
+    assert_eq!(outputs, vec![0, 2, 4]);
+    assert_eq!(errors, vec![1, 3, 5]);
+}
+
+#[tokio::test]
+async fn test_parallel_map_stream() {
+    let counter = InFlightCounter::default();
+    let input = tokio_stream::iter(0..12);
+    let mut outputs: Vec<u32> = parallel_map_stream(input, &counter, 5).collect().await;
+    outputs.sort();
+    assert_eq!(outputs, (0..12).collect::<Vec<_>>());
+    assert_eq!(counter.max.load(Ordering::SeqCst), 5);
+
+    let input = tokio_stream::iter(0..10);
+    let outputs: Vec<u64> = parallel_map_stream_ordered(input, &ReverseDelay, 3)
+        .collect()
+        .await;
+    assert_eq!(outputs, (0..10).collect::<Vec<_>>());
+}
+
+async fn triple(input: u32) -> u32 {
+    input * 3
+}
+
+#[tokio::test]
+async fn test_processor_fn() {
+    let offset = 10;
+    let capturing = processor_fn(move |input: u32| async move { input + offset });
+    assert_eq!(capturing.process(1).await, 11);
+
+    // function items don't need a cast
+    assert_eq!(processor_fn(triple).process(2).await, 6);
+
+    let async_closure = processor_fn(async |input: u32| input + 10);
+    assert_eq!(async_closure.and_then(capturing).process(1).await, 21);
+
+    let shared = Arc::new(final_processor_fn(triple));
+    assert_eq!(FinalProcessor::process(shared, 3).await, 9);
+
+    let by_ref = ref_processor_fn(|deps: &Vec<u32>, index: usize| {
+        let value = deps[index];
+        async move { value * 2 }
+    });
+    let deps = vec![1, 2, 3];
+    assert_eq!(by_ref.process(&deps, 2).await, 6);
+}
+
+#[derive(Debug)]
+struct Scale(u32);
+
+impl FinalProcessor<u32, u32> for Scale {
+    async fn process(state: Arc<Self>, input: u32) -> u32 {
+        tokio::task::yield_now().await;
+        input * state.0
+    }
+}
+
+#[tokio::test]
+async fn test_processor_bridges() {
+    // a final processor in a helper taking a processor
+    let scale = FinalAsProcessor::new(Scale(3));
+    let outputs: Vec<u32> = parallel_map_ordered(1..4, &scale, 2).collect().await;
+    assert_eq!(outputs, vec![3, 6, 9]);
+
+    // a processor spawned onto a task
+    let add_one = Arc::new(ProcessorAsFinal::new(AddOne));
+    let handle = tokio::spawn(FinalProcessor::process(add_one, 1));
+    assert_eq!(handle.await.unwrap(), 2);
+
+    // a ref processor with its dependencies bound
+    let bound = BoundRefProcessor::new(SharedInFlightCounter::default(), 7);
+    assert_eq!(bound.map(|x| x + 1).process(()).await, 8);
+}
+
+#[derive(Debug)]
+struct PanicOnZero;
+
+impl FinalProcessor<u32, u32> for PanicOnZero {
+    #[allow(clippy::panic)]
+    async fn process(_state: Arc<Self>, input: u32) -> u32 {
+        if input == 0 {
+            panic!("zero");
+        }
+        input
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_spawn_process() {
+    let handle = spawn_process(Arc::new(Scale(2)), 21);
+    assert_eq!(handle.await.unwrap(), 42);
+
+    let error = spawn_process(Arc::new(PanicOnZero), 0)
+        .await
+        .map_err(SpawnError::from);
+    assert_eq!(error, Err(SpawnError::Panicked("zero".to_string())));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_parallel_spawn_map() {
+    let results: Vec<_> = parallel_spawn_map(0..5, Arc::new(PanicOnZero), 2)
+        .collect()
+        .await;
+    let mut outputs: Vec<u32> = results.iter().filter_map(|r| r.clone().ok()).collect();
+    outputs.sort();
+    assert_eq!(outputs, vec![1, 2, 3, 4]);
+    assert!(results.contains(&Err(SpawnError::Panicked("zero".to_string()))));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_parallel_spawn_map_bounded() {
+    let counter = Arc::new(InFlightCounter::default());
+    let mut outputs: Vec<u32> = parallel_spawn_map(0..20, counter.clone(), 3)
+        .map(Result::unwrap)
+        .collect()
+        .await;
+    outputs.sort();
+    assert_eq!(outputs, (0..20).collect::<Vec<_>>());
+    assert_eq!(counter.max.load(Ordering::SeqCst), 3);
+}
@@ -0,0 +1,110 @@
+use crate as kanau;
+use crate::processor::{FinalProcessor, Processor};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+#[derive(Debug)]
+struct Greeter {
+    greeting: String,
+}
+
+#[kanau::processor]
+impl Greeter {
+    const DEFAULT: &str = "Hello";
+
+    fn new() -> Self {
+        Self {
+            greeting: Self::DEFAULT.to_string(),
+        }
+    }
+
+    #[processor]
+    async fn greet(&self, name: String) -> String {
+        format!("{}, {name}!", self.greeting().await)
+    }
+
+    // unmarked async methods are left alone, whatever their signature
+    async fn greeting(&self) -> String {
+        self.greeting.clone()
+    }
+
+    async fn shout(&self, name: String) -> String {
+        format!("{}, {name}!!", self.greeting)
+    }
+
+    #[kanau::processor]
+    async fn count(self: Arc<Self>, names: Vec<String>) -> usize {
+        names.len()
+    }
+}
+
+#[derive(Debug)]
+struct Wrapper<T> {
+    value: T,
+}
+
+#[kanau::processor]
+impl<T: Clone + Send + Sync> Wrapper<T> {
+    #[processor]
+    async fn pair(&self, input: u32) -> (u32, T) {
+        (input, self.value.clone())
+    }
+}
+
+#[derive(Debug)]
+struct Users {
+    names: HashMap<u32, String>,
+}
+
+/// Find the name of a user.
+#[kanau::processor]
+async fn find_user(users: &Users, suffix: &String, id: u32) -> Option<String> {
+    users.names.get(&id).map(|name| format!("{name}{suffix}"))
+}
+
+#[kanau::processor]
+#[inline]
+async fn no_dependency(input: u32) {
+    let _ = input;
+}
+
+#[tokio::test]
+async fn test_processor_impl_block() {
+    // the other items are left alone
+    let greeter = Greeter::new();
+    assert_eq!(
+        Processor::process(&greeter, "kanau".to_string()).await,
+        "Hello, kanau!"
+    );
+    // the method is kept
+    assert_eq!(greeter.greet("world".to_string()).await, "Hello, world!");
+    assert_eq!(greeter.shout("world".to_string()).await, "Hello, world!!");
+
+    let count = FinalProcessor::process(Arc::new(greeter), vec!["a".to_string()]).await;
+    assert_eq!(count, 1);
+}
+
+#[tokio::test]
+async fn test_processor_generic_impl_block() {
+    let wrapper = Wrapper { value: "x" };
+    assert_eq!(wrapper.process(1).await, (1, "x"));
+}
+
+#[tokio::test]
+async fn test_processor_fn() {
+    let processor = FindUser {
+        users: Users {
+            names: HashMap::from([(1, "alice".to_string())]),
+        },
+        suffix: "!".to_string(),
+    };
+    assert_eq!(processor.process(1).await, Some("alice!".to_string()));
+    assert_eq!(processor.process(2).await, None);
+
+    NoDependency {}.process(1).await;
+}
+
+#[test]
+fn test_processor_compile_fail() {
+    trybuild::TestCases::new().compile_fail("tests/ui/processor/*.rs");
+}
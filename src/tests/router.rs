@@ -0,0 +1,83 @@
+use crate::layer::LayerFn;
+use crate::processor::{ArcProcessor, Processor, ProcessorExt, processor_fn};
+use crate::router::{NoRouteError, Router};
+
+#[derive(Debug, Clone)]
+struct Message {
+    kind: &'static str,
+    value: u32,
+}
+
+#[derive(Debug, PartialEq)]
+enum HandleError {
+    NoRoute(&'static str),
+}
+
+impl From<NoRouteError<&'static str>> for HandleError {
+    fn from(e: NoRouteError<&'static str>) -> Self {
+        HandleError::NoRoute(e.key)
+    }
+}
+
+fn message(kind: &'static str, value: u32) -> Message {
+    Message { kind, value }
+}
+
+#[tokio::test]
+async fn test_router_no_route() {
+    let router = Router::new(|m: &Message| m.kind)
+        .route(
+            "add",
+            processor_fn(|m: Message| async move { Ok(m.value + 1) }),
+        )
+        .route(
+            "double",
+            processor_fn(|m: Message| async move { Ok(m.value * 2) }),
+        );
+    assert_eq!(router.len(), 2);
+    assert!(router.has_route(&"add"));
+
+    assert_eq!(router.process(message("add", 1)).await, Ok(2));
+    assert_eq!(router.process(message("double", 3)).await, Ok(6));
+    assert_eq!(
+        router.process(message("unknown", 3)).await,
+        Err(HandleError::NoRoute("unknown"))
+    );
+}
+
+#[tokio::test]
+async fn test_router_fallback_and_layers() {
+    let times_ten = LayerFn::new(|p: ArcProcessor<Message, u32>| p.map(|x| x * 10));
+    let plus_one = LayerFn::new(|p: ArcProcessor<Message, u32>| p.map(|x| x + 1));
+    let router =
+        Router::with_fallback(|m: &Message| m.kind, processor_fn(|_: Message| async { 1 }))
+            .route("id", processor_fn(|m: Message| async move { m.value }))
+            .layer_existing_routes(&times_ten)
+            .route("late", processor_fn(|m: Message| async move { m.value }))
+            .layer_route(&"id", &plus_one);
+
+    // the per-route layer is the outermost one
+    assert_eq!(router.process(message("id", 2)).await, 21);
+    // routes registered after are not wrapped
+    assert_eq!(router.process(message("late", 2)).await, 2);
+    // the existing fallback is wrapped
+    assert_eq!(router.clone().process(message("unknown", 2)).await, 10);
+
+    let router = router.fallback(processor_fn(|m: Message| async move { m.value + 100 }));
+    assert_eq!(router.process(message("unknown", 2)).await, 102);
+}
+
+#[tokio::test]
+async fn test_router_with_layer() {
+    // the layer wraps every call, the no-route error included
+    let default_zero = LayerFn::new(
+        |p: Router<&'static str, Message, Result<u32, HandleError>>| {
+            p.map(|result| result.or(Ok::<u32, HandleError>(0)))
+        },
+    );
+    let router = Router::new(|m: &Message| m.kind)
+        .route("id", processor_fn(|m: Message| async move { Ok(m.value) }))
+        .with_layer(&default_zero);
+    assert_eq!(router.process(message("id", 2)).await, Ok(2));
+    assert_eq!(router.process(message("unknown", 2)).await, Ok(0));
+}
@@ -0,0 +1,70 @@
+use crate::processor::Processor;
+use crate::tower::{ProcessorService, ServiceProcessor};
+use std::future::poll_fn;
+use std::task::Poll;
+use std::time::Duration;
+use tower::util::BoxCloneService;
+use tower_service::Service;
+
+#[derive(Debug, Clone)]
+struct Divide;
+
+impl Processor<(u32, u32), Result<u32, String>> for Divide {
+    async fn process(&self, (a, b): (u32, u32)) -> Result<u32, String> {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        a.checked_div(b)
+            .ok_or_else(|| "division by zero".to_string())
+    }
+}
+
+#[tokio::test]
+async fn test_processor_service() {
+    let mut service = ProcessorService::new(Divide);
+    poll_fn(|cx| service.poll_ready(cx)).await.unwrap();
+    assert_eq!(service.call((6, 3)).await, Ok(2));
+    poll_fn(|cx| service.poll_ready(cx)).await.unwrap();
+    assert_eq!(
+        service.call((6, 0)).await,
+        Err("division by zero".to_string())
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_processor_service_concurrency_limit() {
+    let mut first = ProcessorService::new(Divide).with_concurrency_limit(1);
+    let mut second = first.clone();
+
+    poll_fn(|cx| first.poll_ready(cx)).await.unwrap();
+    let pending = first.call((4, 2));
+    let ready = poll_fn(|cx| Poll::Ready(second.poll_ready(cx))).await;
+    assert!(ready.is_pending());
+
+    assert_eq!(pending.await, Ok(2));
+    poll_fn(|cx| second.poll_ready(cx)).await.unwrap();
+    assert_eq!(second.call((9, 3)).await, Ok(3));
+}
+
+#[tokio::test]
+async fn test_processor_service_zero_concurrency_limit() {
+    let mut service = ProcessorService::new(Divide).with_concurrency_limit(0);
+    let ready = poll_fn(|cx| Poll::Ready(service.poll_ready(cx))).await;
+    assert!(ready.is_ready());
+    assert_eq!(service.call((4, 2)).await, Ok(2));
+}
+
+#[tokio::test]
+async fn test_service_processor() {
+    let processor = ServiceProcessor::new(ProcessorService::new(Divide));
+    assert_eq!(processor.process((8, 2)).await, Ok(4));
+    assert!(processor.process((8, 0)).await.is_err());
+}
+
+#[tokio::test]
+async fn test_service_processor_box_clone_service() {
+    // `BoxCloneService` is `Send` but not `Sync`
+    let service: BoxCloneService<(u32, u32), u32, String> =
+        BoxCloneService::new(ProcessorService::new(Divide));
+    let processor = ServiceProcessor::new(service);
+    assert_eq!(processor.process((9, 3)).await, Ok(3));
+    assert!(processor.process((9, 0)).await.is_err());
+}
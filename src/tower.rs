@@ -0,0 +1,155 @@
+use crate::processor::Processor;
+use std::fmt::Debug;
+use std::future::poll_fn;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio_util::sync::PollSemaphore;
+use tower_service::Service;
+
+/// ## ProcessorService
+///
+/// A [Processor] that returns a [Result], used as a [tower_service::Service].
+///
+/// The processor is shared behind an [Arc], so the service is cheap to clone and the returned
+/// futures are `'static`. By default, the service is always ready. With a concurrency limit,
+/// [Service::poll_ready] waits until a permit is available, and the permit is held until the
+/// returned future completes.
+pub struct ProcessorService<I, O, E, P: Processor<I, Result<O, E>>> {
+    processor: Arc<P>,
+    semaphore: Option<PollSemaphore>,
+    permit: Option<OwnedSemaphorePermit>,
+    _phantom: PhantomData<(I, O, E)>,
+}
+
+impl<I, O, E, P: Processor<I, Result<O, E>>> ProcessorService<I, O, E, P> {
+    /// Create a new service from a processor.
+    pub fn new(processor: P) -> Self {
+        Self::from_arc(Arc::new(processor))
+    }
+
+    /// Create a new service from a shared processor.
+    pub fn from_arc(processor: Arc<P>) -> Self {
+        Self {
+            processor,
+            semaphore: None,
+            permit: None,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Limit how many calls run at the same time, across all the clones of this service.
+    ///
+    /// A `limit` of `0` is treated as `1`, otherwise the service would never be ready.
+    pub fn with_concurrency_limit(self, limit: usize) -> Self {
+        self.with_semaphore(Arc::new(Semaphore::new(limit.max(1))))
+    }
+
+    /// Limit how many calls run at the same time with a shared semaphore.
+    ///
+    /// Closing the semaphore lifts the limit.
+    pub fn with_semaphore(self, semaphore: Arc<Semaphore>) -> Self {
+        Self {
+            semaphore: Some(PollSemaphore::new(semaphore)),
+            permit: None,
+            ..self
+        }
+    }
+}
+
+impl<I, O, E, P: Processor<I, Result<O, E>>> Clone for ProcessorService<I, O, E, P> {
+    fn clone(&self) -> Self {
+        Self {
+            processor: self.processor.clone(),
+            semaphore: self.semaphore.clone(),
+            // a permit is acquired by `poll_ready` of each clone
+            permit: None,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<I, O, E, P: Processor<I, Result<O, E>> + Debug> Debug for ProcessorService<I, O, E, P> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProcessorService")
+            .field("processor", &self.processor)
+            .field("concurrency_limited", &self.semaphore.is_some())
+            .finish()
+    }
+}
+
+impl<
+    I: Send + 'static,
+    O: Send + 'static,
+    E: Send + 'static,
+    P: Processor<I, Result<O, E>> + Send + Sync + 'static,
+> Service<I> for ProcessorService<I, O, E, P>
+{
+    type Response = O;
+    type Error = E;
+    type Future = Pin<Box<dyn Future<Output = Result<O, E>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        if let (None, Some(semaphore)) = (&self.permit, &mut self.semaphore) {
+            // a closed semaphore lifts the limit
+            self.permit = match semaphore.poll_acquire(cx) {
+                Poll::Ready(permit) => permit,
+                Poll::Pending => return Poll::Pending,
+            };
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: I) -> Self::Future {
+        let processor = self.processor.clone();
+        let permit = self.permit.take();
+        Box::pin(async move {
+            let output = processor.process(req).await;
+            drop(permit);
+            output
+        })
+    }
+}
+
+/// ## ServiceProcessor
+///
+/// A [tower_service::Service] used as a [Processor].
+///
+/// The service is cloned for every call, then the call waits until the clone is ready.
+/// The output is `Result<S::Response, S::Error>`.
+#[derive(Debug, Clone)]
+pub struct ServiceProcessor<S> {
+    service: S,
+}
+
+impl<S> ServiceProcessor<S> {
+    /// Create a new processor from a service.
+    pub fn new(service: S) -> Self {
+        Self { service }
+    }
+
+    /// Get the wrapped service.
+    pub fn into_inner(self) -> S {
+        self.service
+    }
+}
+
+impl<I, S> Processor<I, Result<S::Response, S::Error>> for ServiceProcessor<S>
+where
+    I: Send,
+    S: Service<I> + Clone + Send,
+    S::Response: Send,
+    S::Error: Send,
+    S::Future: Send,
+{
+    fn process(&self, input: I) -> impl Future<Output = Result<S::Response, S::Error>> + Send {
+        // clone before the future, so it doesn't borrow `self` and `S` needn't be `Sync`
+        let mut service = self.service.clone();
+        async move {
+            poll_fn(|cx| service.poll_ready(cx)).await?;
+            service.call(input).await
+        }
+    }
+}
@@ -0,0 +1,14 @@
+struct Counter {
+    count: u32,
+}
+
+#[kanau::processor]
+impl Counter {
+    #[processor]
+    async fn add(&mut self, input: u32) -> u32 {
+        self.count += input;
+        self.count
+    }
+}
+
+fn main() {}
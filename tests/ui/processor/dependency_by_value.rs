@@ -0,0 +1,10 @@
+struct Config {
+    factor: u32,
+}
+
+#[kanau::processor]
+async fn scale(config: Config, input: u32) -> u32 {
+    input * config.factor
+}
+
+fn main() {}
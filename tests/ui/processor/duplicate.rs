@@ -0,0 +1,16 @@
+struct Math;
+
+#[kanau::processor]
+impl Math {
+    #[processor]
+    async fn double(&self, input: u32) -> u32 {
+        input * 2
+    }
+
+    #[processor]
+    async fn square(&self, input: u32) -> u32 {
+        input * input
+    }
+}
+
+fn main() {}
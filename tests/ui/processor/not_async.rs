@@ -0,0 +1,6 @@
+#[kanau::processor]
+fn double(input: u32) -> u32 {
+    input * 2
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use std::rc::Rc;
+
+#[kanau::processor]
+async fn count(input: u32) -> u32 {
+    let counter = Rc::new(input);
+    tokio::task::yield_now().await;
+    *counter
+}
+
+fn main() {}
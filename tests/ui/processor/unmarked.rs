@@ -0,0 +1,10 @@
+struct Math;
+
+#[kanau::processor]
+impl Math {
+    async fn double(&self, input: u32) -> u32 {
+        input * 2
+    }
+}
+
+fn main() {}